@@ -1,14 +1,16 @@
 #![cfg(feature = "benching")]
 
-use std::num::{NonZeroU8, NonZeroU16};
+use std::num::{NonZeroU16, NonZeroU32};
 
 use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 
-const BEGINNER_DIMENSIONS: (u8, u8) = (9, 9);
-const INTERMEDIATE_DIMENSIONS: (u8, u8) = (16, 16);
-const EXPERT_DIMENSIONS: (u8, u8) = (30, 16);
+const BEGINNER_DIMENSIONS: (u16, u16) = (9, 9);
+const INTERMEDIATE_DIMENSIONS: (u16, u16) = (16, 16);
+const EXPERT_DIMENSIONS: (u16, u16) = (30, 16);
 
-const BOARD_SIZES: [(u8, u8); 10] = [
+const LARGEST_DIMENSIONS: (u16, u16) = (255, 255);
+
+const BOARD_SIZES: [(u16, u16); 12] = [
     (4, 4),
     (5, 5),
     BEGINNER_DIMENSIONS,
@@ -19,6 +21,8 @@ const BOARD_SIZES: [(u8, u8); 10] = [
     EXPERT_DIMENSIONS,
     (30, 30),
     (50, 50),
+    (100, 100),
+    LARGEST_DIMENSIONS,
 ];
 
 /// Worst case scenario for opening a cell. Since there's only one mine, pretty
@@ -27,7 +31,7 @@ pub fn open_cell_one_mine(c: &mut Criterion) {
     let mut group = c.benchmark_group("Opening cells, one mine");
     for (width, height) in BOARD_SIZES.iter() {
         group.throughput(Throughput::Elements(u64::from(
-            (*width as u16) * (*height as u16) - 1,
+            (*width as u32) * (*height as u32) - 1,
         )));
         let input_string = format!("{width}x{height}");
         let input = (*width, *height);
@@ -38,17 +42,16 @@ pub fn open_cell_one_mine(c: &mut Criterion) {
                 b.iter_custom(|iterations| {
                     let mut timed_duration = std::time::Duration::ZERO;
                     for _ in 0..iterations {
-                        let mut board = minesweeper_rs::Board::create_custom(
-                            NonZeroU8::new(width).unwrap(),
-                            NonZeroU8::new(height).unwrap(),
-                            NonZeroU16::new(1).unwrap(),
+                        let mut board = minesweeper_rs::Board::create_custom_seeded(
+                            NonZeroU16::new(width).unwrap(),
+                            NonZeroU16::new(height).unwrap(),
+                            NonZeroU32::new(1).unwrap(),
+                            rand::random::<u64>(),
                         )
                         .unwrap();
                         let (center_x, center_y) = (width / 2, height / 2);
-                        let random_seed = rand::random::<u64>();
-                        board.generate_mines_with_seed(center_x, center_y, random_seed);
+                        board.generate_mines_at(center_x, center_y);
                         let start = std::time::Instant::now();
-                        #[allow(clippy::unit_arg)]
                         std::hint::black_box(board.open_cell(
                             std::hint::black_box(center_x),
                             std::hint::black_box(center_y),
@@ -71,7 +74,7 @@ pub fn open_cell_defaults(c: &mut Criterion) {
     ];
     for ((width, height), mines) in choices.iter() {
         group.throughput(Throughput::Elements(u64::from(
-            (*width as u16) * (*height as u16) - mines,
+            (*width as u32) * (*height as u32) - mines,
         )));
         let input_string = format!("{width}x{height}");
         let input = (*width, *height);
@@ -82,17 +85,16 @@ pub fn open_cell_defaults(c: &mut Criterion) {
                 b.iter_custom(|iterations| {
                     let mut timed_duration = std::time::Duration::ZERO;
                     for _ in 0..iterations {
-                        let mut board = minesweeper_rs::Board::create_custom(
-                            NonZeroU8::new(width).unwrap(),
-                            NonZeroU8::new(height).unwrap(),
-                            NonZeroU16::new(*mines).unwrap(),
+                        let mut board = minesweeper_rs::Board::create_custom_seeded(
+                            NonZeroU16::new(width).unwrap(),
+                            NonZeroU16::new(height).unwrap(),
+                            NonZeroU32::new(*mines).unwrap(),
+                            rand::random::<u64>(),
                         )
                         .unwrap();
                         let (center_x, center_y) = (width / 2, height / 2);
-                        let random_seed = rand::random::<u64>();
-                        board.generate_mines_with_seed(center_x, center_y, random_seed);
+                        board.generate_mines_at(center_x, center_y);
                         let start = std::time::Instant::now();
-                        #[allow(clippy::unit_arg)]
                         std::hint::black_box(board.open_cell(
                             std::hint::black_box(center_x),
                             std::hint::black_box(center_y),
@@ -115,21 +117,20 @@ pub fn open_cell_almost_all_mines(c: &mut Criterion) {
             BenchmarkId::from_parameter(input_string),
             &input,
             |b, &(width, height)| {
-                let mines = width as u16 * height as u16 - 9;
+                let mines = width as u32 * height as u32 - 9;
                 b.iter_custom(|iterations| {
                     let mut timed_duration = std::time::Duration::ZERO;
                     for _ in 0..iterations {
-                        let mut board = minesweeper_rs::Board::create_custom(
-                            NonZeroU8::new(width).unwrap(),
-                            NonZeroU8::new(height).unwrap(),
-                            NonZeroU16::new(mines).unwrap(),
+                        let mut board = minesweeper_rs::Board::create_custom_seeded(
+                            NonZeroU16::new(width).unwrap(),
+                            NonZeroU16::new(height).unwrap(),
+                            NonZeroU32::new(mines).unwrap(),
+                            rand::random::<u64>(),
                         )
                         .unwrap();
                         let (center_x, center_y) = (width / 2, height / 2);
-                        let random_seed = rand::random::<u64>();
-                        board.generate_mines_with_seed(center_x, center_y, random_seed);
+                        board.generate_mines_at(center_x, center_y);
                         let start = std::time::Instant::now();
-                        #[allow(clippy::unit_arg)]
                         std::hint::black_box(board.open_cell(
                             std::hint::black_box(center_x),
                             std::hint::black_box(center_y),
@@ -148,10 +149,10 @@ pub fn open_cell_25_percent(c: &mut Criterion) {
     for (width, height) in BOARD_SIZES.iter() {
         let input_string = format!("{width}x{height}");
         let input = (*width, *height);
-        let board_area = (*width as u16) * (*height as u16);
-        let mines = u16::clamp(board_area / 4, 1, board_area - 1);
+        let board_area = (*width as u32) * (*height as u32);
+        let mines = u32::clamp(board_area / 4, 1, board_area - 1);
         group.throughput(Throughput::Elements(u64::from(
-            (*width as u16) * (*height as u16) - mines,
+            (*width as u32) * (*height as u32) - mines,
         )));
         group.bench_with_input(
             BenchmarkId::from_parameter(input_string),
@@ -160,17 +161,16 @@ pub fn open_cell_25_percent(c: &mut Criterion) {
                 b.iter_custom(|iterations| {
                     let mut timed_duration = std::time::Duration::ZERO;
                     for _ in 0..iterations {
-                        let mut board = minesweeper_rs::Board::create_custom(
-                            NonZeroU8::new(width).unwrap(),
-                            NonZeroU8::new(height).unwrap(),
-                            NonZeroU16::new(mines).unwrap(),
+                        let mut board = minesweeper_rs::Board::create_custom_seeded(
+                            NonZeroU16::new(width).unwrap(),
+                            NonZeroU16::new(height).unwrap(),
+                            NonZeroU32::new(mines).unwrap(),
+                            rand::random::<u64>(),
                         )
                         .unwrap();
                         let (center_x, center_y) = (width / 2, height / 2);
-                        let random_seed = rand::random::<u64>();
-                        board.generate_mines_with_seed(center_x, center_y, random_seed);
+                        board.generate_mines_at(center_x, center_y);
                         let start = std::time::Instant::now();
-                        #[allow(clippy::unit_arg)]
                         std::hint::black_box(board.open_cell(
                             std::hint::black_box(center_x),
                             std::hint::black_box(center_y),
@@ -189,10 +189,10 @@ pub fn open_cell_50_percent(c: &mut Criterion) {
     for (width, height) in BOARD_SIZES.iter() {
         let input_string = format!("{width}x{height}");
         let input = (*width, *height);
-        let board_area = (*width as u16) * (*height as u16);
-        let mines = u16::clamp(board_area / 2, 1, board_area - 1);
+        let board_area = (*width as u32) * (*height as u32);
+        let mines = u32::clamp(board_area / 2, 1, board_area - 1);
         group.throughput(Throughput::Elements(u64::from(
-            (*width as u16) * (*height as u16) - mines,
+            (*width as u32) * (*height as u32) - mines,
         )));
         group.bench_with_input(
             BenchmarkId::from_parameter(input_string),
@@ -201,17 +201,16 @@ pub fn open_cell_50_percent(c: &mut Criterion) {
                 b.iter_custom(|iterations| {
                     let mut timed_duration = std::time::Duration::ZERO;
                     for _ in 0..iterations {
-                        let mut board = minesweeper_rs::Board::create_custom(
-                            NonZeroU8::new(width).unwrap(),
-                            NonZeroU8::new(height).unwrap(),
-                            NonZeroU16::new(mines).unwrap(),
+                        let mut board = minesweeper_rs::Board::create_custom_seeded(
+                            NonZeroU16::new(width).unwrap(),
+                            NonZeroU16::new(height).unwrap(),
+                            NonZeroU32::new(mines).unwrap(),
+                            rand::random::<u64>(),
                         )
                         .unwrap();
                         let (center_x, center_y) = (width / 2, height / 2);
-                        let random_seed = rand::random::<u64>();
-                        board.generate_mines_with_seed(center_x, center_y, random_seed);
+                        board.generate_mines_at(center_x, center_y);
                         let start = std::time::Instant::now();
-                        #[allow(clippy::unit_arg)]
                         std::hint::black_box(board.open_cell(
                             std::hint::black_box(center_x),
                             std::hint::black_box(center_y),
@@ -230,10 +229,10 @@ pub fn open_cell_75_percent(c: &mut Criterion) {
     for (width, height) in BOARD_SIZES.iter() {
         let input_string = format!("{width}x{height}");
         let input = (*width, *height);
-        let board_area = (*width as u16) * (*height as u16);
-        let mines = u16::clamp(board_area * 3 / 4, 1, board_area - 1);
+        let board_area = (*width as u32) * (*height as u32);
+        let mines = u32::clamp(board_area * 3 / 4, 1, board_area - 1);
         group.throughput(Throughput::Elements(u64::from(
-            (*width as u16) * (*height as u16) - mines,
+            (*width as u32) * (*height as u32) - mines,
         )));
         group.bench_with_input(
             BenchmarkId::from_parameter(input_string),
@@ -242,17 +241,16 @@ pub fn open_cell_75_percent(c: &mut Criterion) {
                 b.iter_custom(|iterations| {
                     let mut timed_duration = std::time::Duration::ZERO;
                     for _ in 0..iterations {
-                        let mut board = minesweeper_rs::Board::create_custom(
-                            NonZeroU8::new(width).unwrap(),
-                            NonZeroU8::new(height).unwrap(),
-                            NonZeroU16::new(mines).unwrap(),
+                        let mut board = minesweeper_rs::Board::create_custom_seeded(
+                            NonZeroU16::new(width).unwrap(),
+                            NonZeroU16::new(height).unwrap(),
+                            NonZeroU32::new(mines).unwrap(),
+                            rand::random::<u64>(),
                         )
                         .unwrap();
                         let (center_x, center_y) = (width / 2, height / 2);
-                        let random_seed = rand::random::<u64>();
-                        board.generate_mines_with_seed(center_x, center_y, random_seed);
+                        board.generate_mines_at(center_x, center_y);
                         let start = std::time::Instant::now();
-                        #[allow(clippy::unit_arg)]
                         std::hint::black_box(board.open_cell(
                             std::hint::black_box(center_x),
                             std::hint::black_box(center_y),
@@ -266,6 +264,62 @@ pub fn open_cell_75_percent(c: &mut Criterion) {
     }
 }
 
+/// Flagging used to be followed by a scan of every unopened cell to count the
+/// remaining mines, which is what made it slow on large boards.
+pub fn toggle_flag_largest(c: &mut Criterion) {
+    let (width, height) = LARGEST_DIMENSIONS;
+    let mines = width as u32 * height as u32 / 5;
+    let mut board = minesweeper_rs::Board::create_custom_seeded(
+        NonZeroU16::new(width).unwrap(),
+        NonZeroU16::new(height).unwrap(),
+        NonZeroU32::new(mines).unwrap(),
+        rand::random::<u64>(),
+    )
+    .unwrap();
+    board.generate_mines_at(0, 0);
+    c.bench_function("Toggling a flag and counting mines, 255x255", |b| {
+        b.iter(|| {
+            board.toggle_flag(
+                std::hint::black_box(width - 1),
+                std::hint::black_box(height - 1),
+            );
+            std::hint::black_box(board.get_remaining_mine_count())
+        })
+    });
+}
+
+/// Opens the last safe cell of a board, which has to detect the win.
+pub fn win_detection_largest(c: &mut Criterion) {
+    let (width, height) = LARGEST_DIMENSIONS;
+    let area = width as usize * height as usize;
+    // A single mine in the far corner, so that opening the opposite corner floods every other
+    // cell apart from the one that is opened last
+    let mut grid = vec![false; area];
+    grid[area - 1] = true;
+    let mut board = minesweeper_rs::Board::create_from_grid(
+        NonZeroU16::new(width).unwrap(),
+        NonZeroU16::new(height).unwrap(),
+        &grid,
+    )
+    .unwrap();
+    board.toggle_flag(width - 2, height - 1);
+    board.open_cell(0, 0);
+    board.toggle_flag(width - 2, height - 1);
+    c.bench_function("Winning by opening the last cell, 255x255", |b| {
+        b.iter_batched(
+            || board.clone(),
+            |mut board| {
+                board.open_cell(
+                    std::hint::black_box(width - 2),
+                    std::hint::black_box(height - 1),
+                );
+                board
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+}
+
 criterion_group!(
     benches,
     open_cell_one_mine,
@@ -274,5 +328,7 @@ criterion_group!(
     open_cell_25_percent,
     open_cell_50_percent,
     open_cell_75_percent,
+    toggle_flag_largest,
+    win_detection_largest,
 );
 criterion_main!(benches);
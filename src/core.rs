@@ -1,2 +1,8 @@
+pub mod analysis;
+pub mod assist;
+mod bitset;
 pub mod board;
 pub mod cell;
+pub mod daily;
+pub mod format;
+pub mod solver;
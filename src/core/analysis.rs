@@ -0,0 +1,220 @@
+use std::{collections::VecDeque, num::NonZeroU16};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{board::Board, cell::AdjacentMines, solver};
+
+/// What a board's mine layout looks like, for judging how hard it is to clear.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardAnalysis {
+    /// Groups of touching zeros, each of which is opened by a single click.
+    pub openings: u32,
+    /// Numbers that do not border an opening and have to be opened one by one.
+    pub isolated_numbers: u32,
+    /// The share of the board's cells that are mines.
+    pub mine_density: f64,
+    /// How many times the solver had to guess to clear the board, not counting
+    /// the first click.
+    pub forced_guesses: u32,
+    safe_cell_count: u32,
+}
+
+/// A rough grade of how hard a board is, see [`BoardAnalysis::get_difficulty`].
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Easy => "Easy",
+                Self::Normal => "Normal",
+                Self::Hard => "Hard",
+            }
+        )
+    }
+}
+
+impl BoardAnalysis {
+    /// On boards with a typical layout, the 3BV is roughly this many times the
+    /// number of safe cells times the mine density to the power of 1.5.
+    const TYPICAL_3BV_FACTOR: f64 = 4.87;
+    /// Boards whose 3BV is below this share of the typical 3BV are easy.
+    const EASY_RELATIVE_3BV: f64 = 0.8;
+    /// Boards whose 3BV is above this share of the typical 3BV are hard.
+    const HARD_RELATIVE_3BV: f64 = 1.25;
+    /// Boards that need at least one forced guess for every this many points
+    /// of 3BV are hard.
+    const HARD_3BV_PER_GUESS: u32 = 12;
+    /// See [`Board::get_3bv`].
+    pub const fn get_3bv(&self) -> u32 {
+        self.openings + self.isolated_numbers
+    }
+    /// Grades the board by comparing its 3BV to what is typical for its size
+    /// and mine density, and by how much guessing it needs. A board that is
+    /// much quicker to clear than usual without any guessing is easy, while
+    /// one that is much slower to clear, or needs guessing often, is hard.
+    pub fn get_difficulty(&self) -> Difficulty {
+        let typical_3bv =
+            Self::TYPICAL_3BV_FACTOR * self.safe_cell_count as f64 * self.mine_density.powf(1.5);
+        let relative_3bv = self.get_3bv() as f64 / typical_3bv;
+        if self.forced_guesses == 0 && relative_3bv < Self::EASY_RELATIVE_3BV {
+            Difficulty::Easy
+        } else if relative_3bv > Self::HARD_RELATIVE_3BV
+            || self.forced_guesses * Self::HARD_3BV_PER_GUESS >= self.get_3bv()
+        {
+            Difficulty::Hard
+        } else {
+            Difficulty::Normal
+        }
+    }
+}
+
+impl Board {
+    /// Returns the board's 3BV (Bechtel's Board Benchmark Value), which is the
+    /// fewest clicks needed to clear it without flagging: one for each
+    /// opening, where a group of touching zeros is opened by a single click,
+    /// and one for each number that does not border an opening. Returns `None`
+    /// until the mines have been placed.
+    pub fn get_3bv(&self) -> Option<u32> {
+        let (openings, isolated_numbers) = self.count_openings()?;
+        Some(openings + isolated_numbers)
+    }
+    /// Analyses the board's mine layout, ignoring whatever the player has done
+    /// on it so far. This runs the solver over the whole board, so it can take
+    /// a while on large boards. Returns `None` until the mines have been
+    /// placed.
+    pub fn analyse(&self) -> Option<BoardAnalysis> {
+        let (openings, isolated_numbers) = self.count_openings()?;
+        let mined_coordinates: Vec<_> = self
+            .iter_cells()
+            .filter(|(_, cell)| cell.is_mine())
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        let width = NonZeroU16::new(self.get_width()).unwrap();
+        let height = NonZeroU16::new(self.get_height()).unwrap();
+        let untouched = Board::create_with_mines(width, height, &mined_coordinates)
+            .ok()?
+            .with_topology(self.get_topology());
+        let cell_count = self.get_width() as u32 * self.get_height() as u32;
+        Some(BoardAnalysis {
+            openings,
+            isolated_numbers,
+            mine_density: self.get_mine_count() as f64 / cell_count as f64,
+            forced_guesses: solver::count_forced_guesses(&untouched),
+            safe_cell_count: cell_count - self.get_mine_count(),
+        })
+    }
+    /// Counts the board's openings and the numbers that do not border any of
+    /// them.
+    fn count_openings(&self) -> Option<(u32, u32)> {
+        if !self.has_mines() {
+            return None;
+        }
+        let width = self.get_width() as usize;
+        let is_zero = |x: u16, y: u16| {
+            self.get_cell(x, y).unwrap().adjacent_mines() == Some(AdjacentMines::Zero)
+        };
+        // Every safe cell that gets opened by clicking on an opening
+        let mut covered = vec![false; width * self.get_height() as usize];
+        let mut openings = 0;
+        for y in 0..self.get_height() {
+            for x in 0..self.get_width() {
+                if covered[y as usize * width + x as usize] || !is_zero(x, y) {
+                    continue;
+                }
+                openings += 1;
+                covered[y as usize * width + x as usize] = true;
+                let mut queue = VecDeque::from([(x, y)]);
+                while let Some((x, y)) = queue.pop_front() {
+                    for (x, y) in self.get_surrounding_coordinates(x, y) {
+                        let index = y as usize * width + x as usize;
+                        if covered[index] {
+                            continue;
+                        }
+                        covered[index] = true;
+                        if is_zero(x, y) {
+                            queue.push_back((x, y));
+                        }
+                    }
+                }
+            }
+        }
+        // Numbers that no opening reaches have to be clicked on one by one
+        let isolated_numbers = self
+            .iter_cells()
+            .filter(|((x, y), cell)| !covered[*y as usize * width + *x as usize] && !cell.is_mine())
+            .count() as u32;
+        Some((openings, isolated_numbers))
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::core::board::Topology;
+    #[test]
+    fn test_3bv() {
+        // One opening on the left, and three numbers on the right that it does not
+        // reach
+        let board = Board::create_from_text("....*\n...*.\n....*").unwrap();
+        assert_eq!(board.get_3bv(), Some(4));
+        // Every number is isolated when there is no opening
+        let board = Board::create_from_text(".*.\n*.*\n.*.").unwrap();
+        assert_eq!(board.get_3bv(), Some(5));
+        assert_eq!(Board::create_beginner().get_3bv(), None);
+    }
+    #[test]
+    fn test_3bv_wrapping() {
+        // The two zeros at either end only form one opening once the edges are joined
+        let board = Board::create_from_text("...*...\n...*...").unwrap();
+        assert_eq!(board.get_3bv(), Some(2));
+        let board = board.with_topology(Topology::Wrapping);
+        assert_eq!(board.get_3bv(), Some(1));
+    }
+    #[test]
+    fn test_analyse() {
+        let board = Board::create_from_text("....*\n...*.\n....*").unwrap();
+        let analysis = board.analyse().unwrap();
+        assert_eq!(analysis.openings, 1);
+        assert_eq!(analysis.isolated_numbers, 3);
+        assert_eq!(analysis.get_3bv(), 4);
+        assert_eq!(analysis.mine_density, 0.2);
+        assert_eq!(analysis.forced_guesses, 0);
+        assert_eq!(Board::create_beginner().analyse(), None);
+    }
+    #[test]
+    fn test_analyse_forced_guesses() {
+        // Every number on the board is a one touching the same mine, so nothing
+        // short of opening all of the other cells gives it away
+        let board = Board::create_from_text(".*\n..").unwrap();
+        assert_eq!(board.analyse().unwrap().forced_guesses, 2);
+        // Progress made on the board does not change its analysis
+        let mut played = board.clone();
+        played.open_cell(0, 0);
+        assert_eq!(played.analyse(), board.analyse());
+    }
+    #[test]
+    fn test_difficulty() {
+        // A single opening clears the whole board, when a board like it usually
+        // takes a few clicks
+        let board =
+            Board::create_from_text("......\n......\n......\n......\n....**\n....**").unwrap();
+        assert_eq!(board.analyse().unwrap().get_difficulty(), Difficulty::Easy);
+        let board = Board::create_from_text(".*\n..").unwrap();
+        assert_eq!(board.analyse().unwrap().get_difficulty(), Difficulty::Hard);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::core::board::Board;
+
+impl Board {
+    /// Returns the unflagged cells that have to be mines because a number
+    /// touches exactly as many unopened cells as it shows. Mines that have
+    /// exploded without ending the game count towards the number as well.
+    pub fn get_obvious_mines(&self) -> Vec<(u16, u16)> {
+        let mut obvious_mines = Vec::new();
+        for ((x, y), cell) in self.iter_cells() {
+            let (true, Some(adjacent_mines)) = (cell.is_open(), cell.adjacent_mines()) else {
+                continue;
+            };
+            let possible_mines: Vec<_> = self
+                .iter_neighbours(x, y)
+                .filter(|(_, neighbour)| !neighbour.is_open() || neighbour.is_mine())
+                .collect();
+            if possible_mines.len() != u8::from(adjacent_mines) as usize {
+                continue;
+            }
+            obvious_mines.extend(
+                possible_mines
+                    .into_iter()
+                    .filter(|(_, neighbour)| !neighbour.is_open() && !neighbour.is_flagged())
+                    .map(|(coordinate, _)| coordinate),
+            );
+        }
+        obvious_mines.sort_unstable();
+        obvious_mines.dedup();
+        obvious_mines
+    }
+    /// Returns the numbers whose flags already account for all of their mines
+    /// whilst they still touch unflagged cells, which chording on them would
+    /// open.
+    pub fn get_satisfied_numbers(&self) -> Vec<(u16, u16)> {
+        self.iter_cells()
+            .filter(|&((x, y), cell)| {
+                let (true, Some(adjacent_mines)) = (cell.is_open(), cell.adjacent_mines()) else {
+                    return false;
+                };
+                let found_mines = self
+                    .iter_neighbours(x, y)
+                    .filter(|(_, neighbour)| {
+                        neighbour.is_flagged() || (neighbour.is_open() && neighbour.is_mine())
+                    })
+                    .count();
+                found_mines == u8::from(adjacent_mines) as usize
+                    && self
+                        .iter_neighbours(x, y)
+                        .any(|(_, neighbour)| !neighbour.is_open() && !neighbour.is_flagged())
+            })
+            .map(|(coordinate, _)| coordinate)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    #[test]
+    fn test_obvious_mines() {
+        let mut board = Board::create_from_text("..*.\n..*.\n..*.").unwrap();
+        assert!(board.get_obvious_mines().is_empty());
+        board.open_cell(0, 0);
+        // Every number beside the right column only touches mines
+        assert_eq!(board.get_obvious_mines(), [(2, 0), (2, 1), (2, 2)]);
+        board.toggle_flag(2, 0);
+        assert_eq!(board.get_obvious_mines(), [(2, 1), (2, 2)]);
+    }
+    #[test]
+    fn test_satisfied_numbers() {
+        let mut board = Board::create_from_text(".*.\n...\n...").unwrap();
+        board.open_cell(0, 0);
+        assert!(board.get_satisfied_numbers().is_empty());
+        board.toggle_flag(1, 0);
+        assert_eq!(board.get_satisfied_numbers(), [(0, 0)]);
+        board.chord_cell(0, 0);
+        // Once the cells around it are open, chording on it would do nothing
+        assert!(!board.get_satisfied_numbers().contains(&(0, 0)));
+    }
+}
@@ -0,0 +1,89 @@
+/// A set of cell indices with a fixed capacity, stored as one bit per cell.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct BitSet {
+    words: Box<[u64]>,
+}
+
+impl BitSet {
+    /// Creates an empty set that can hold the indices `0..capacity`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(64)].into_boxed_slice(),
+        }
+    }
+    /// Creates a set holding every index in `0..capacity`.
+    pub fn filled(capacity: usize) -> Self {
+        let mut set = Self::new(capacity);
+        set.words.fill(u64::MAX);
+        if !capacity.is_multiple_of(64) {
+            // Clears the bits past the end, so that they are not counted as members
+            *set.words.last_mut().unwrap() = (1 << (capacity % 64)) - 1;
+        }
+        set
+    }
+    pub fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+    /// Adds `index` to the set, returning whether it was not already there.
+    pub fn insert(&mut self, index: usize) -> bool {
+        let word = &mut self.words[index / 64];
+        let bit = 1 << (index % 64);
+        let inserted = *word & bit == 0;
+        *word |= bit;
+        inserted
+    }
+    /// Removes `index` from the set, returning whether it was there.
+    pub fn remove(&mut self, index: usize) -> bool {
+        let word = &mut self.words[index / 64];
+        let bit = 1 << (index % 64);
+        let removed = *word & bit != 0;
+        *word &= !bit;
+        removed
+    }
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, word)| {
+                let mut word = *word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    // Clears the lowest set bit
+                    word &= word - 1;
+                    Some(word_index * 64 + bit)
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    #[test]
+    fn test_bitset_insert_remove() {
+        let mut set = BitSet::new(100);
+        assert!(!set.contains(70));
+        assert!(set.insert(70));
+        assert!(!set.insert(70));
+        assert!(set.contains(70));
+        assert!(set.insert(3));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [3, 70]);
+        assert_eq!(set.iter().count(), 2);
+        assert!(set.remove(70));
+        assert!(!set.remove(70));
+        assert_eq!(set.iter().count(), 1);
+    }
+    #[test]
+    fn test_bitset_filled() {
+        let set = BitSet::filled(70);
+        assert_eq!(set.iter().count(), 70);
+        assert!(set.contains(69));
+        assert!(!set.contains(70));
+        assert_eq!(BitSet::filled(128).iter().count(), 128);
+    }
+}
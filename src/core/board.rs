@@ -1,97 +1,364 @@
 use std::{
     collections::{HashSet, VecDeque},
-    num::{NonZeroU8, NonZeroU16},
+    iter,
+    num::{NonZeroU16, NonZeroU32},
 };
 
 use rand::prelude::*;
+use thiserror::Error;
 
-use crate::core::{cell, cell::Cell};
+use crate::core::{
+    bitset::BitSet,
+    cell,
+    cell::{Cell, Mark},
+    solver,
+};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum BoardState {
     InProgress,
     Won,
     Lost,
 }
 
-#[derive(Debug)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum GenerationMode {
+    /// Mines are placed anywhere that the first-click rules allow.
+    #[default]
+    Random,
+    /// Mines are placed so that the board can be solved from the first click
+    /// by logic alone, without ever having to guess.
+    NoGuess,
+}
+
+/// How much of the board around the first cell opened is kept free of mines.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum FirstClickPolicy {
+    /// Mines may be placed anywhere, including on the first cell opened.
+    Unprotected,
+    /// Only the first cell opened is kept free of mines.
+    SafeCell,
+    /// The first cell opened and its neighbours are kept free of mines, so that
+    /// the first click always opens a zero. If there are too many mines for
+    /// this, only the first cell is kept free.
+    #[default]
+    SafeOpening,
+}
+
+impl FirstClickPolicy {
+    pub const ALL: &'static [FirstClickPolicy] = &[
+        FirstClickPolicy::Unprotected,
+        FirstClickPolicy::SafeCell,
+        FirstClickPolicy::SafeOpening,
+    ];
+}
+
+impl std::fmt::Display for FirstClickPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FirstClickPolicy::Unprotected => "Can be a mine",
+            FirstClickPolicy::SafeCell => "Safe cell",
+            FirstClickPolicy::SafeOpening => "Safe opening",
+        })
+    }
+}
+
+/// How the cells of a board connect to their neighbours.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum Topology {
+    /// Cells on the edges have fewer neighbours than the rest.
+    #[default]
+    Bounded,
+    /// The left and right edges are joined, as are the top and bottom edges, so
+    /// that every cell has eight neighbours.
+    Wrapping,
+    /// The cells are hexagons, with every odd row shifted half a cell to the
+    /// right, so that each cell has at most six neighbours.
+    Hexagonal,
+}
+
+impl Topology {
+    pub const ALL: &'static [Topology] =
+        &[Topology::Bounded, Topology::Wrapping, Topology::Hexagonal];
+}
+
+impl std::fmt::Display for Topology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Topology::Bounded => "Square",
+            Topology::Wrapping => "Wrap-around",
+            Topology::Hexagonal => "Hexagonal",
+        })
+    }
+}
+
+/// A move made by the player, as recorded in a board's history.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Move {
+    Open(u16, u16),
+    Chord(u16, u16),
+    ToggleFlag(u16, u16),
+    CycleMark(u16, u16),
+}
+
+/// What a move did to the board.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MoveOutcome {
+    /// Every cell that was opened, in the order they were opened.
+    pub revealed: Vec<(u16, u16)>,
+    /// Whether one of the cells opened was a mine.
+    pub hit_mine: bool,
+    /// Whether a flag or question mark was placed or removed.
+    pub flag_changed: bool,
+}
+
+impl MoveOutcome {
+    /// Returns whether the move left the board exactly as it was.
+    pub fn is_no_op(&self) -> bool {
+        self.revealed.is_empty() && !self.flag_changed
+    }
+}
+
+/// A move that changed the board, along with what is needed to reverse it.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct Action {
+    performed_move: Move,
+    opened_coordinates: Vec<(u16, u16)>,
+    /// The mark each opened cell had, in the same order, so that undoing the
+    /// move puts question marks back. Missing from games saved before this was
+    /// kept, in which case the cells are closed without a mark.
+    #[serde(default)]
+    opened_marks: Vec<Mark>,
+    previous_state: BoardState,
+}
+
+/// A board along with everything that has happened on it, which can be
+/// serialized to save a game in progress.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Board {
     cells: Box<[Cell]>,
-    width: NonZeroU8,
-    height: NonZeroU8,
-    mine_count: NonZeroU16,
-    unopened_coordinates: HashSet<(u8, u8)>,
-    mined_coordinates: HashSet<(u8, u8)>,
+    width: NonZeroU16,
+    height: NonZeroU16,
+    mine_count: NonZeroU32,
+    unopened_cells: BitSet,
+    mined_cells: BitSet,
+    // Kept up to date as cells change, so that neither the remaining mine count nor the check
+    // for a win has to look through every cell
+    flagged_count: u32,
+    unopened_safe_count: u32,
+    exploded_count: u32,
+    lives: NonZeroU32,
+    seed: u64,
+    generation_mode: GenerationMode,
+    /// Set when no solvable layout could be found for a no-guess board, which
+    /// was then generated randomly instead.
+    #[serde(default)]
+    no_guess_failed: bool,
+    #[serde(default)]
+    topology: Topology,
+    #[serde(default)]
+    first_click_policy: FirstClickPolicy,
     first_open: bool,
     state: BoardState,
+    history: Vec<Action>,
+    undone_moves: Vec<Move>,
+    used_undo: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum BoardError {
+    #[error("a board cannot be a single cell")]
     InvalidBoardSize,
-    TooManyMines { max_mines: NonZeroU16 }, /* The number of mines equals to or exceeds the
+    #[error("too many mines, the board can hold at most {max_mines}")]
+    TooManyMines { max_mines: NonZeroU32 }, /* The number of mines equals to or exceeds the
                                              * board area */
+    #[error("the width and height can be at most {max_size}")]
+    TooLarge { max_size: NonZeroU16 }, // The width or height exceeds `Board::MAX_SIZE`
+    #[error("a board needs at least one mine")]
+    NoMines,
+    #[error("the mine at ({x}, {y}) is off the board or given twice")]
+    InvalidMine { x: u16, y: u16 }, // The mine is outside of the board or was given twice
+    #[error("the grid should have {expected} cells")]
+    GridSizeMismatch { expected: usize }, // The grid does not have one entry per cell
+}
+
+/// Why a move could not be made, as returned by the `try_*` moves such as
+/// [`Board::try_open_cell`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Error)]
+pub enum MoveError {
+    #[error("({x}, {y}) is not on the board")]
+    OutOfBounds { x: u16, y: u16 },
+    #[error("the game is already over")]
+    GameOver,
+    #[error("the cell at ({x}, {y}) is flagged")]
+    Flagged { x: u16, y: u16 },
 }
 
 impl Board {
+    /// The largest width and height a board can have.
+    pub const MAX_SIZE: NonZeroU16 = NonZeroU16::new(4096).unwrap();
     /// # Safety
     /// This function does not perform any validation on the parameters, and
     /// therefore it is up to the caller to ensure that the parameters are
     /// valid. Specifically, `width` and `height` both cannot be one, and `mine
-    /// count` must be less than `width * height`.
+    /// count` must be less than `width * height`. Neither `width` nor `height`
+    /// can exceed [`Board::MAX_SIZE`].
     #[inline]
     unsafe fn create_unchecked(
-        width: NonZeroU8,
-        height: NonZeroU8,
-        mine_count: NonZeroU16,
+        width: NonZeroU16,
+        height: NonZeroU16,
+        mine_count: NonZeroU32,
+        seed: u64,
     ) -> Self {
-        let cells =
-            vec![Cell::new(); (width.get() as usize) * (height.get() as usize)].into_boxed_slice();
-        let unopened_coordinates = (0..width.get())
-            .flat_map(|x| (0..height.get()).map(move |y| (x, y)))
-            .collect();
-        let mined_coordinates = HashSet::with_capacity(mine_count.get() as usize);
+        let area = (width.get() as usize) * (height.get() as usize);
+        let cells = vec![Cell::new(); area].into_boxed_slice();
         Self {
             cells,
             width,
             height,
             mine_count,
-            unopened_coordinates,
-            mined_coordinates,
+            unopened_cells: BitSet::filled(area),
+            mined_cells: BitSet::new(area),
+            flagged_count: 0,
+            unopened_safe_count: area as u32 - mine_count.get(),
+            exploded_count: 0,
+            lives: NonZeroU32::MIN,
+            seed,
+            generation_mode: GenerationMode::Random,
+            no_guess_failed: false,
+            topology: Topology::Bounded,
+            first_click_policy: FirstClickPolicy::SafeOpening,
             first_open: true,
             state: BoardState::InProgress,
+            history: Vec::new(),
+            undone_moves: Vec::new(),
+            used_undo: false,
         }
     }
     pub fn create_custom(
-        width: NonZeroU8,
-        height: NonZeroU8,
-        mine_count: NonZeroU16,
+        width: NonZeroU16,
+        height: NonZeroU16,
+        mine_count: NonZeroU32,
+    ) -> Result<Self, BoardError> {
+        Self::create_custom_seeded(width, height, mine_count, rand::random())
+    }
+    /// Creates a board whose mine layout is derived from `seed`. Two boards
+    /// with the same dimensions, mine count and seed will always generate the
+    /// same layout when the same cell is opened first.
+    pub fn create_custom_seeded(
+        width: NonZeroU16,
+        height: NonZeroU16,
+        mine_count: NonZeroU32,
+        seed: u64,
     ) -> Result<Self, BoardError> {
         if width.get() == 1 && height.get() == 1 {
             // A 1x1 board either can have no mines (we need at least one mine, or it is not
             // a game) or be entirely filled with mines (which is not a game either)
             return Err(BoardError::InvalidBoardSize);
         }
-        let board_area = (width.get() as u16) * (height.get() as u16);
+        if width > Self::MAX_SIZE || height > Self::MAX_SIZE {
+            return Err(BoardError::TooLarge {
+                max_size: Self::MAX_SIZE,
+            });
+        }
+        let board_area = (width.get() as u32) * (height.get() as u32);
         if mine_count.get() >= board_area {
             // The number of mines is equal to the board area (which is not a game) or
             // exceeds it (which is impossible to construct)
             return Err(BoardError::TooManyMines {
-                max_mines: NonZeroU16::new(board_area - 1).unwrap(),
+                max_mines: NonZeroU32::new(board_area - 1).unwrap(),
             });
         }
         Ok(unsafe {
             // SAFETY: All values have been validated above
-            Self::create_unchecked(width, height, mine_count)
+            Self::create_unchecked(width, height, mine_count, seed)
         })
     }
+    /// Creates a board with mines on exactly the given coordinates, skipping
+    /// random generation. The layout must follow the same rules as
+    /// [`Board::create_custom`], and every coordinate must be on the board and
+    /// appear only once.
+    pub fn create_with_mines(
+        width: NonZeroU16,
+        height: NonZeroU16,
+        mined_coordinates: &[(u16, u16)],
+    ) -> Result<Self, BoardError> {
+        // Anything that does not fit in a `u32` is larger than any board area anyway
+        let mine_count = u32::try_from(mined_coordinates.len()).unwrap_or(u32::MAX);
+        let mine_count = NonZeroU32::new(mine_count).ok_or(BoardError::NoMines)?;
+        let mut board = Self::create_custom(width, height, mine_count)?;
+        let mut seen_coordinates = HashSet::with_capacity(mined_coordinates.len());
+        for &(x, y) in mined_coordinates {
+            if x >= width.get() || y >= height.get() || !seen_coordinates.insert((x, y)) {
+                return Err(BoardError::InvalidMine { x, y });
+            }
+        }
+        board.place_mines(mined_coordinates.iter().copied());
+        Ok(board)
+    }
+    /// Creates a board from a grid with one entry per cell, row by row, where
+    /// `true` marks a mine. See [`Board::create_with_mines`] for the rules the
+    /// layout must follow.
+    pub fn create_from_grid(
+        width: NonZeroU16,
+        height: NonZeroU16,
+        grid: &[bool],
+    ) -> Result<Self, BoardError> {
+        let expected = width.get() as usize * height.get() as usize;
+        if grid.len() != expected {
+            return Err(BoardError::GridSizeMismatch { expected });
+        }
+        let mined_coordinates = (0..height.get())
+            .flat_map(|y| (0..width.get()).map(move |x| (x, y)))
+            .zip(grid)
+            .filter_map(|(coordinate, is_mine)| is_mine.then_some(coordinate))
+            .collect::<Vec<_>>();
+        Self::create_with_mines(width, height, &mined_coordinates)
+    }
     pub fn create_beginner() -> Self {
         unsafe {
             // SAFETY: All values are hardcoded, non-zero and within the valid range
             Self::create_unchecked(
-                NonZeroU8::new_unchecked(9),
-                NonZeroU8::new_unchecked(9),
-                NonZeroU16::new_unchecked(10),
+                NonZeroU16::new_unchecked(9),
+                NonZeroU16::new_unchecked(9),
+                NonZeroU32::new_unchecked(10),
+                rand::random(),
             )
         }
     }
@@ -99,9 +366,10 @@ impl Board {
         unsafe {
             // SAFETY: All values are hardcoded, non-zero and within the valid range
             Self::create_unchecked(
-                NonZeroU8::new_unchecked(16),
-                NonZeroU8::new_unchecked(16),
-                NonZeroU16::new_unchecked(40),
+                NonZeroU16::new_unchecked(16),
+                NonZeroU16::new_unchecked(16),
+                NonZeroU32::new_unchecked(40),
+                rand::random(),
             )
         }
     }
@@ -109,44 +377,315 @@ impl Board {
         unsafe {
             // SAFETY: All values are hardcoded, non-zero and within the valid range
             Self::create_unchecked(
-                NonZeroU8::new_unchecked(30),
-                NonZeroU8::new_unchecked(16),
-                NonZeroU16::new_unchecked(99),
+                NonZeroU16::new_unchecked(30),
+                NonZeroU16::new_unchecked(16),
+                NonZeroU32::new_unchecked(99),
+                rand::random(),
             )
         }
     }
-    pub fn get_cell(&self, x: u8, y: u8) -> Option<&Cell> {
+    /// Sets how the mines are generated once the first cell is opened. Has no
+    /// effect if the mines have already been generated.
+    pub fn with_generation_mode(mut self, generation_mode: GenerationMode) -> Self {
+        if self.first_open {
+            self.generation_mode = generation_mode;
+        }
+        self
+    }
+    /// Sets how much of the board around the first cell opened is kept free of
+    /// mines. Has no effect if the mines have already been generated.
+    pub fn with_first_click_policy(mut self, first_click_policy: FirstClickPolicy) -> Self {
+        if self.first_open {
+            self.first_click_policy = first_click_policy;
+        }
+        self
+    }
+    /// Sets how many mines can be opened before the game is lost. With more
+    /// than one life, opening a mine reveals it as exploded and play carries
+    /// on. Has no effect once a cell has been opened.
+    pub fn with_lives(mut self, lives: NonZeroU32) -> Self {
+        if !self.has_opened_cell() {
+            self.lives = lives;
+        }
+        self
+    }
+    /// Sets how the edges of the board connect. Has no effect once a cell has
+    /// been opened. If the mines have already been placed, the adjacent mine
+    /// counts are worked out again for the new topology.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        if self.has_opened_cell() {
+            return self;
+        }
+        self.topology = topology;
+        if self.has_mines() {
+            let mined_coordinates = self
+                .mined_cells
+                .iter()
+                .map(|index| linear_to_coordinate(index, self.width))
+                .collect::<Vec<_>>();
+            self.cells.fill(Cell::new());
+            self.mined_cells = BitSet::new(self.cells.len());
+            self.place_mines(mined_coordinates);
+        }
+        self
+    }
+    /// Creates a new, unopened board with the same settings as this one but a
+    /// different seed, which is used for starting a new game.
+    pub fn new_game(&self) -> Self {
+        unsafe {
+            // SAFETY: The dimensions and mine count were validated when this board was
+            // created.
+            Self::create_unchecked(self.width, self.height, self.mine_count, rand::random())
+        }
+        .with_generation_mode(self.generation_mode)
+        .with_topology(self.topology)
+        .with_first_click_policy(self.first_click_policy)
+        .with_lives(self.lives)
+    }
+    pub fn get_cell(&self, x: u16, y: u16) -> Option<&Cell> {
         if x >= self.get_width() || y >= self.get_height() {
             return None;
         }
         self.cells.get(coordinate_to_linear(x, y, self.width))
     }
-    pub fn get_cell_mut(&mut self, x: u8, y: u8) -> Option<&mut Cell> {
+    pub fn get_cell_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
         if x >= self.get_width() || y >= self.get_height() {
             return None;
         }
         self.cells.get_mut(coordinate_to_linear(x, y, self.width))
     }
-    pub const fn get_width(&self) -> u8 {
+    /// Returns every cell of the board along with its coordinates, row by row.
+    pub fn iter_cells(&self) -> impl Iterator<Item = ((u16, u16), &Cell)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (linear_to_coordinate(index, self.width), cell))
+    }
+    /// Returns the cells next to `(x, y)` along with their coordinates, or
+    /// nothing if `(x, y)` is not on the board. See
+    /// [`Board::get_surrounding_coordinates`] for which cells count as next to
+    /// each other.
+    pub fn iter_neighbours(&self, x: u16, y: u16) -> impl Iterator<Item = ((u16, u16), &Cell)> {
+        let is_on_board = x < self.get_width() && y < self.get_height();
+        self.get_surrounding_coordinates(x, y)
+            .filter(move |_| is_on_board)
+            .map(|(x, y)| ((x, y), self.get_cell(x, y).unwrap()))
+    }
+    /// Returns the unopened cells that are next to an opened cell, which are
+    /// the cells the revealed numbers say something about.
+    pub fn iter_frontier(&self) -> impl Iterator<Item = ((u16, u16), &Cell)> {
+        self.iter_cells().filter(|&((x, y), cell)| {
+            !cell.is_open() && self.iter_neighbours(x, y).any(|(_, cell)| cell.is_open())
+        })
+    }
+    /// Returns the flagged cells, leaving out question marks.
+    pub fn iter_flagged(&self) -> impl Iterator<Item = ((u16, u16), &Cell)> {
+        self.iter_cells().filter(|(_, cell)| cell.is_flagged())
+    }
+    pub const fn get_width(&self) -> u16 {
         self.width.get()
     }
-    pub const fn get_height(&self) -> u8 {
+    pub const fn get_height(&self) -> u16 {
         self.height.get()
     }
-    pub const fn get_mine_count(&self) -> u16 {
+    pub const fn get_mine_count(&self) -> u32 {
         self.mine_count.get()
     }
+    pub const fn get_seed(&self) -> u64 {
+        self.seed
+    }
+    /// Returns how the board's mines are generated. A no-guess board that no
+    /// solvable layout could be found for reports [`GenerationMode::Random`],
+    /// since that is how its mines were placed in the end, though new games
+    /// made from it still try for a no-guess layout.
+    pub const fn get_generation_mode(&self) -> GenerationMode {
+        if self.no_guess_failed {
+            GenerationMode::Random
+        } else {
+            self.generation_mode
+        }
+    }
+    pub const fn get_topology(&self) -> Topology {
+        self.topology
+    }
+    pub const fn get_first_click_policy(&self) -> FirstClickPolicy {
+        self.first_click_policy
+    }
+    pub const fn get_lives(&self) -> u32 {
+        self.lives.get()
+    }
+    /// Returns how many more mines can be opened before the game is lost,
+    /// counting the one that would lose it.
+    pub const fn get_remaining_lives(&self) -> u32 {
+        self.lives.get().saturating_sub(self.exploded_count)
+    }
+    /// Returns whether the mines have been placed, which happens when the
+    /// first cell is opened.
+    pub const fn has_mines(&self) -> bool {
+        !self.first_open
+    }
     pub fn get_remaining_mine_count(&self) -> i32 {
-        // Subtracts how many cells have been flagged from how many mines there are
-        (self.mine_count.get() as i32)
-            - (self
-                .unopened_coordinates
-                .iter()
-                .filter(|(x, y)| self.get_cell(*x, *y).unwrap().is_flagged())
-                .count() as i32)
+        // Subtracts how many cells have been flagged from how many mines there are.
+        // Question marks are only reminders, so they are not counted, whilst mines that
+        // exploded without ending the game have been found.
+        (self.mine_count.get() as i32) - (self.flagged_count as i32) - (self.exploded_count as i32)
+    }
+    pub fn open_cell(&mut self, x: u16, y: u16) -> MoveOutcome {
+        self.perform(Move::Open(x, y))
+    }
+    /// Chords the cell at `(x, y)`, which means that if the cell is open and
+    /// the number of flags surrounding the cell is equal to the number of
+    /// adjacent mines, then all the unflagged surrounding cells are opened.
+    /// Question marks count as unflagged, so those cells are opened too.
+    pub fn chord_cell(&mut self, x: u16, y: u16) -> MoveOutcome {
+        self.perform(Move::Chord(x, y))
+    }
+    pub fn toggle_flag(&mut self, x: u16, y: u16) -> MoveOutcome {
+        self.perform(Move::ToggleFlag(x, y))
+    }
+    /// Moves the cell at `(x, y)` on to its next mark, going from no mark to a
+    /// flag, then to a question mark and back to no mark.
+    pub fn cycle_mark(&mut self, x: u16, y: u16) -> MoveOutcome {
+        self.perform(Move::CycleMark(x, y))
+    }
+    /// Like [`Board::open_cell`], but fails instead of doing nothing when the
+    /// cell is off the board or flagged, or when the game is over.
+    pub fn try_open_cell(&mut self, x: u16, y: u16) -> Result<MoveOutcome, MoveError> {
+        self.check_move(x, y)?;
+        if self.get_cell(x, y).unwrap().is_flagged() {
+            return Err(MoveError::Flagged { x, y });
+        }
+        Ok(self.open_cell(x, y))
+    }
+    /// Like [`Board::chord_cell`], but fails instead of doing nothing when the
+    /// cell is off the board or the game is over.
+    pub fn try_chord_cell(&mut self, x: u16, y: u16) -> Result<MoveOutcome, MoveError> {
+        self.check_move(x, y)?;
+        Ok(self.chord_cell(x, y))
+    }
+    /// Like [`Board::toggle_flag`], but fails instead of doing nothing when the
+    /// cell is off the board or the game is over.
+    pub fn try_toggle_flag(&mut self, x: u16, y: u16) -> Result<MoveOutcome, MoveError> {
+        self.check_move(x, y)?;
+        Ok(self.toggle_flag(x, y))
+    }
+    /// Like [`Board::cycle_mark`], but fails instead of doing nothing when the
+    /// cell is off the board or the game is over.
+    pub fn try_cycle_mark(&mut self, x: u16, y: u16) -> Result<MoveOutcome, MoveError> {
+        self.check_move(x, y)?;
+        Ok(self.cycle_mark(x, y))
+    }
+    /// Checks the rules that every move has to follow.
+    fn check_move(&self, x: u16, y: u16) -> Result<(), MoveError> {
+        if x >= self.get_width() || y >= self.get_height() {
+            return Err(MoveError::OutOfBounds { x, y });
+        }
+        if !matches!(self.state, BoardState::InProgress) {
+            return Err(MoveError::GameOver);
+        }
+        Ok(())
+    }
+    /// Reverses the most recent move that changed the board, including a move
+    /// that lost the game. The move that placed the mines cannot be undone, nor
+    /// can anything before it, since a different first click could then be
+    /// made on a layout that was generated around this one. Once this has been
+    /// used the board is marked as having used undo for good, even if the move
+    /// is redone. Returns whether there was a move to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(action) = self.history.pop() else {
+            return false;
+        };
+        match action.performed_move {
+            Move::ToggleFlag(x, y) => {
+                self.flag(x, y);
+            }
+            // There are three marks, so cycling twice more steps back to the previous one
+            Move::CycleMark(x, y) => {
+                self.cycle(x, y);
+                self.cycle(x, y);
+            }
+            Move::Open(..) | Move::Chord(..) => (),
+        }
+        let marks = action
+            .opened_marks
+            .into_iter()
+            .chain(iter::repeat(Mark::None));
+        for ((x, y), mark) in action.opened_coordinates.into_iter().zip(marks) {
+            self.close_single(x, y, mark);
+        }
+        self.state = action.previous_state;
+        self.used_undo = true;
+        self.undone_moves.push(action.performed_move);
+        true
+    }
+    /// Makes the most recently undone move again. Returns whether there was a
+    /// move to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(undone_move) = self.undone_moves.pop() else {
+            return false;
+        };
+        self.apply(undone_move);
+        true
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.undone_moves.is_empty()
+    }
+    pub const fn has_used_undo(&self) -> bool {
+        self.used_undo
+    }
+    /// Applies a new move, recording it in the history if it changed anything.
+    /// Any undone moves are forgotten, since they no longer follow on from the
+    /// current board.
+    fn perform(&mut self, new_move: Move) -> MoveOutcome {
+        let outcome = self.apply(new_move);
+        if !outcome.is_no_op() {
+            self.undone_moves.clear();
+        }
+        outcome
+    }
+    /// Applies a move to the board, adding it to the history if it changed
+    /// anything. Once the game is over no move changes anything.
+    fn apply(&mut self, performed_move: Move) -> MoveOutcome {
+        let previous_state = self.state;
+        let mut outcome = MoveOutcome::default();
+        if !matches!(previous_state, BoardState::InProgress) {
+            return outcome;
+        }
+        let first_open = self.first_open;
+        let mut opened = Vec::new();
+        match performed_move {
+            Move::Open(x, y) => self.open(x, y, &mut opened),
+            Move::Chord(x, y) => self.chord(x, y, &mut opened),
+            Move::ToggleFlag(x, y) => outcome.flag_changed = self.flag(x, y),
+            Move::CycleMark(x, y) => outcome.flag_changed = self.cycle(x, y),
+        }
+        let opened_marks;
+        (outcome.revealed, opened_marks) = opened.into_iter().unzip();
+        outcome.hit_mine = outcome
+            .revealed
+            .iter()
+            .any(|(x, y)| self.get_cell(*x, *y).unwrap().is_mine());
+        if first_open && !self.first_open {
+            // The layout depends on where the mines were placed from, so neither this
+            // move nor anything before it can be undone
+            self.history.clear();
+        } else if !outcome.is_no_op() {
+            self.history.push(Action {
+                performed_move,
+                opened_coordinates: outcome.revealed.clone(),
+                opened_marks,
+                previous_state,
+            });
+        }
+        outcome
     }
-    pub fn open_cell(&mut self, x: u8, y: u8) {
-        if !self.unopened_coordinates.contains(&(x, y)) {
+    /// Opens the cell at `(x, y)`, flooding outwards if it has no adjacent
+    /// mines, and adds every cell opened to `opened` along with the mark it had.
+    fn open(&mut self, x: u16, y: u16, opened: &mut Vec<((u16, u16), Mark)>) {
+        if !self.is_unopened(x, y) {
             return;
         }
         if self.first_open {
@@ -154,17 +693,21 @@ impl Board {
             // prevents the first cell to be opened on a mine, and thus losing the game
             // immediately.
             self.generate_mines(x, y);
-            self.first_open = false;
         }
         let cell = self.get_cell(x, y).unwrap();
         if cell.is_flagged() {
             return;
         }
-        self.unopened_coordinates.remove(&(x, y));
-        let cell = self.get_cell_mut(x, y).unwrap();
-        cell.open();
+        let mark = cell.get_mark().unwrap_or_default();
+        self.open_single(x, y);
+        opened.push(((x, y), mark));
+        let cell = self.get_cell(x, y).unwrap();
         if cell.is_mine() {
-            self.state = BoardState::Lost;
+            // The mine stays open as an exploded mine, and only ends the game once every
+            // life has been used
+            if self.exploded_count >= self.lives.get() {
+                self.state = BoardState::Lost;
+            }
             return;
         }
         if let Some(cell::AdjacentMines::Zero) = cell.adjacent_mines() {
@@ -172,20 +715,17 @@ impl Board {
                 .get_surrounding_coordinates(x, y)
                 .collect::<VecDeque<_>>();
             while let Some((surrounding_x, surrounding_y)) = queue.pop_front() {
-                if !self
-                    .unopened_coordinates
-                    .contains(&(surrounding_x, surrounding_y))
-                {
+                if !self.is_unopened(surrounding_x, surrounding_y) {
                     continue;
                 }
                 let cell = self.get_cell(surrounding_x, surrounding_y).unwrap();
                 if cell.is_flagged() {
                     continue;
                 }
-                self.unopened_coordinates
-                    .remove(&(surrounding_x, surrounding_y));
-                let cell = self.get_cell_mut(surrounding_x, surrounding_y).unwrap();
-                cell.open();
+                let mark = cell.get_mark().unwrap_or_default();
+                self.open_single(surrounding_x, surrounding_y);
+                opened.push(((surrounding_x, surrounding_y), mark));
+                let cell = self.get_cell(surrounding_x, surrounding_y).unwrap();
                 if let Some(cell::AdjacentMines::Zero) = cell.adjacent_mines() {
                     for (surrounding_x, surrounding_y) in
                         self.get_surrounding_coordinates(surrounding_x, surrounding_y)
@@ -195,53 +735,171 @@ impl Board {
                 }
             }
         }
-        if self.unopened_coordinates == self.mined_coordinates {
+        if self.unopened_safe_count == 0 && matches!(self.state, BoardState::InProgress) {
             self.state = BoardState::Won;
         }
     }
-
-    /// Chords the cell at `(x, y)`, which means that if the cell is open and
-    /// the number of flags surrounding the cell is equal to the number of
-    /// adjacent mines, then all the unflagged surrounding cells are opened.
-    pub fn chord_cell(&mut self, x: u8, y: u8) {
+    fn has_opened_cell(&self) -> bool {
+        self.exploded_count > 0
+            || self.unopened_safe_count as usize
+                != self.cells.len() - self.mine_count.get() as usize
+    }
+    fn is_unopened(&self, x: u16, y: u16) -> bool {
+        x < self.get_width()
+            && y < self.get_height()
+            && self
+                .unopened_cells
+                .contains(coordinate_to_linear(x, y, self.width))
+    }
+    /// Opens a single cell and updates the counters to match.
+    fn open_single(&mut self, x: u16, y: u16) {
+        let index = coordinate_to_linear(x, y, self.width);
+        let cell = &mut self.cells[index];
+        cell.open();
+        if cell.is_open() && self.unopened_cells.remove(index) {
+            if cell.is_mine() {
+                self.exploded_count += 1;
+            } else {
+                self.unopened_safe_count -= 1;
+            }
+        }
+    }
+    /// Closes a single opened cell again with the given mark and updates the
+    /// counters to match.
+    fn close_single(&mut self, x: u16, y: u16, mark: Mark) {
+        let index = coordinate_to_linear(x, y, self.width);
+        let cell = &mut self.cells[index];
+        cell.close(mark);
+        if self.unopened_cells.insert(index) {
+            if cell.is_mine() {
+                self.exploded_count -= 1;
+            } else {
+                self.unopened_safe_count += 1;
+            }
+        }
+    }
+    fn chord(&mut self, x: u16, y: u16, opened: &mut Vec<((u16, u16), Mark)>) {
         let Some(cell) = self.get_cell(x, y) else {
             return;
         };
-        if !cell.is_open() {
+        // An exploded mine has no number to chord on
+        let (true, Some(adjacent_mines)) = (cell.is_open(), cell.adjacent_mines()) else {
             return;
-        }
+        };
         let surrounding_coordinates = self.get_surrounding_coordinates(x, y);
-        // Get rid of any surrounding cells that are already opened (if they're open
-        // then they're obviously safe otherwise the game would be over), and then
-        // partition them into whether they are flagged or not.
+        // Get rid of any surrounding cells that are opened and safe, and then partition
+        // the rest into whether they are known to be mines or not. Exploded mines count
+        // the same as flags, since they have already been found.
         let (flagged, unflagged): (Vec<_>, Vec<_>) = surrounding_coordinates
-            .filter(|(x, y)| self.get_cell(*x, *y).is_some_and(|cell| !cell.is_open()))
-            .partition(|(x, y)| self.get_cell(*x, *y).is_some_and(|cell| cell.is_flagged()));
+            .filter(|(x, y)| {
+                self.get_cell(*x, *y)
+                    .is_some_and(|cell| !cell.is_open() || cell.is_mine())
+            })
+            .partition(|(x, y)| {
+                self.get_cell(*x, *y)
+                    .is_some_and(|cell| cell.is_flagged() || cell.is_open())
+            });
         // If the number of flagged cells does not match the number of adjacent mines,
         // then we do not meet the requirements for opening the unflagged cells.
-        if flagged.len() != cell.adjacent_mines().unwrap() as usize {
+        if flagged.len() != adjacent_mines as usize {
             return;
         }
         // If we reach this point, then we open all the unflagged cells, since the
         // number of flagged cells is equal to the number of adjacent mines.
         for (surrounding_x, surrounding_y) in unflagged {
-            self.open_cell(surrounding_x, surrounding_y);
+            self.open(surrounding_x, surrounding_y, opened);
         }
     }
-    pub fn toggle_flag(&mut self, x: u8, y: u8) {
+    /// Toggles the flag on the cell at `(x, y)`, returning whether the cell was
+    /// unopened and so could be flagged.
+    pub(crate) fn flag(&mut self, x: u16, y: u16) -> bool {
         let Some(cell) = self.get_cell_mut(x, y) else {
-            return;
+            return false;
         };
         if cell.is_open() {
-            return;
+            return false;
         }
         cell.toggle_flag();
+        if cell.is_flagged() {
+            self.flagged_count += 1;
+        } else {
+            self.flagged_count -= 1;
+        }
+        true
+    }
+    /// Cycles the mark on the cell at `(x, y)`, returning whether the cell was
+    /// unopened and so could be marked.
+    pub(crate) fn cycle(&mut self, x: u16, y: u16) -> bool {
+        let Some(cell) = self.get_cell_mut(x, y) else {
+            return false;
+        };
+        if cell.is_open() {
+            return false;
+        }
+        let was_flagged = cell.is_flagged();
+        cell.cycle_mark();
+        match (was_flagged, cell.is_flagged()) {
+            (false, true) => self.flagged_count += 1,
+            (true, false) => self.flagged_count -= 1,
+            _ => (),
+        }
+        true
     }
     pub const fn get_state(&self) -> BoardState {
         self.state
     }
-    fn get_surrounding_coordinates(&self, x: u8, y: u8) -> impl Iterator<Item = (u8, u8)> + use<> {
+    /// Returns the cells next to `(x, y)`. On a wrapping board the neighbours
+    /// continue on the opposite edge, although a board narrower or shorter
+    /// than three cells still has each neighbour only once. On a hexagonal
+    /// board the neighbours are the cells on either side and the two cells
+    /// touching it in each of the rows above and below.
+    pub fn get_surrounding_coordinates(
+        &self,
+        x: u16,
+        y: u16,
+    ) -> impl Iterator<Item = (u16, u16)> + use<> {
         let mut coordinates = tinyvec::ArrayVec::<[_; 8]>::new();
+        if let Topology::Wrapping = self.topology {
+            let (width, height) = (self.get_width(), self.get_height());
+            for x_offset in [width - 1, 0, 1] {
+                for y_offset in [height - 1, 0, 1] {
+                    let x_new = ((x as u32 + x_offset as u32) % width as u32) as u16;
+                    let y_new = ((y as u32 + y_offset as u32) % height as u32) as u16;
+                    if (x_new, y_new) != (x, y) && !coordinates.contains(&(x_new, y_new)) {
+                        coordinates.push((x_new, y_new));
+                    }
+                }
+            }
+            return coordinates.into_iter();
+        }
+        if let Topology::Hexagonal = self.topology {
+            // Odd rows are shifted to the right, so the other row a cell touches leans
+            // left from an even row and right from an odd one
+            let x_leaning = if y.is_multiple_of(2) {
+                x.checked_sub(1)
+            } else {
+                x.checked_add(1)
+            };
+            let y_above = y.checked_sub(1);
+            let y_below = y.checked_add(1);
+            let candidates = [
+                (x.checked_sub(1), Some(y)),
+                (x.checked_add(1), Some(y)),
+                (Some(x), y_above),
+                (x_leaning, y_above),
+                (Some(x), y_below),
+                (x_leaning, y_below),
+            ];
+            coordinates.extend(
+                candidates
+                    .into_iter()
+                    .filter_map(|(x_new, y_new)| Some((x_new?, y_new?)))
+                    .filter(|(x_new, y_new)| {
+                        *x_new < self.get_width() && *y_new < self.get_height()
+                    }),
+            );
+            return coordinates.into_iter();
+        }
         for x_new in x.saturating_sub(1)..=x.saturating_add(1) {
             if x_new >= self.get_width() {
                 continue;
@@ -258,81 +916,141 @@ impl Board {
         }
         coordinates.into_iter()
     }
-    fn generate_mines(&mut self, x: u8, y: u8) {
+    fn generate_mines(&mut self, x: u16, y: u16) {
         if !self.first_open {
             // DO NOT GENERATE MINES if a cell on the board has already been opened.
             return;
         }
-        let mut rng = SmallRng::from_os_rng();
-        let total_area = self.get_width() as u16 * self.get_height() as u16;
+        // `StdRng` is used over `SmallRng` since its output does not depend on the
+        // platform, so a seed produces the same layout everywhere.
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let possible_coordinates = self.get_possible_mine_coordinates(x, y);
+        let mined_coordinates = match self.generation_mode {
+            GenerationMode::Random => possible_coordinates
+                .into_iter()
+                .choose_multiple(&mut rng, self.mine_count.get() as usize),
+            GenerationMode::NoGuess => self
+                .generate_no_guess_layout(&mut rng, x, y, &possible_coordinates)
+                .unwrap_or_else(|| {
+                    self.no_guess_failed = true;
+                    possible_coordinates
+                        .into_iter()
+                        .choose_multiple(&mut rng, self.mine_count.get() as usize)
+                }),
+        };
+        self.place_mines(mined_coordinates);
+    }
+    /// Returns every coordinate a mine may be placed on when `(x, y)` is the
+    /// first cell opened, following the board's first-click policy.
+    fn get_possible_mine_coordinates(&self, x: u16, y: u16) -> Vec<(u16, u16)> {
+        let total_area = self.get_width() as u32 * self.get_height() as u32;
         let surrounding_coordinates = self
             .get_surrounding_coordinates(x, y)
             .collect::<tinyvec::ArrayVec<[_; 8]>>();
+        // A board cannot be solved without guessing from a mine, so no-guess boards
+        // always keep at least the first cell safe
+        let first_click_policy = match (self.first_click_policy, self.generation_mode) {
+            (FirstClickPolicy::Unprotected, GenerationMode::NoGuess) => FirstClickPolicy::SafeCell,
+            (first_click_policy, _) => first_click_policy,
+        };
+        let mut disallowed_coordinates = tinyvec::ArrayVec::<[_; 9]>::new();
+        if first_click_policy != FirstClickPolicy::Unprotected {
+            disallowed_coordinates.push((x, y));
+        }
         // In order to make the game more fun, I try and avoid placing mines in the
         // cells surrounding the cell selected. However, if the number of mines is too
         // high, this may not be possible, and therefore must be accounted for.
         let too_many_mines =
-            total_area - (surrounding_coordinates.len() as u16 + 1) < self.mine_count.get();
-        let mut disallowed_coordinates = tinyvec::array_vec!([_; 9] => (x,y));
+            total_area - (surrounding_coordinates.len() as u32 + 1) < self.mine_count.get();
         // If we have enough space, we will not place mines in the cells surrounding the
         // cell specified
-        if !too_many_mines {
+        if first_click_policy == FirstClickPolicy::SafeOpening && !too_many_mines {
             disallowed_coordinates.extend_from_slice(surrounding_coordinates.as_slice());
         }
-        let possible_coordinates = (0..self.width.get()).flat_map(|x| {
-            (0..self.height.get()).filter_map(move |y| {
-                if disallowed_coordinates.contains(&(x, y)) {
-                    None
-                } else {
-                    Some((x, y))
-                }
+        (0..self.width.get())
+            .flat_map(|x| {
+                (0..self.height.get()).filter_map(move |y| {
+                    if disallowed_coordinates.contains(&(x, y)) {
+                        None
+                    } else {
+                        Some((x, y))
+                    }
+                })
             })
-        });
-        let mined_coordinates =
-            possible_coordinates.choose_multiple(&mut rng, self.mine_count.get() as usize);
-        for (mine_x, mine_y) in mined_coordinates {
-            for (cell_x, cell_y) in self.get_surrounding_coordinates(mine_x, mine_y) {
-                self.get_cell_mut(cell_x, cell_y)
-                    .unwrap()
-                    .increment_adjacent_mines();
+            .collect()
+    }
+    /// Searches for a layout that can be solved from `(x, y)` without guessing.
+    /// Each attempt starts from a random layout, and whenever the solver gets
+    /// stuck a mine bordering the opened area is moved away from it, which is
+    /// repeated a bounded number of times before starting a new attempt.
+    /// Returns `None` if no solvable layout is found in time, so that
+    /// generation always finishes quickly.
+    fn generate_no_guess_layout(
+        &self,
+        rng: &mut StdRng,
+        x: u16,
+        y: u16,
+        possible_coordinates: &[(u16, u16)],
+    ) -> Option<Vec<(u16, u16)>> {
+        const ATTEMPTS: usize = 10;
+        const REPAIRS_PER_ATTEMPT: usize = 50;
+        let mine_count = self.mine_count.get() as usize;
+        for _ in 0..ATTEMPTS {
+            let mut layout = possible_coordinates
+                .iter()
+                .copied()
+                .choose_multiple(rng, mine_count);
+            for _ in 0..REPAIRS_PER_ATTEMPT {
+                let mut candidate = self.clone();
+                candidate.place_mines(layout.iter().copied());
+                let played = solver::play_without_guessing(&candidate, x, y);
+                if let BoardState::Won = played.get_state() {
+                    return Some(layout);
+                }
+                let borders_opened = |(x, y): (u16, u16)| {
+                    played
+                        .get_surrounding_coordinates(x, y)
+                        .any(|(x, y)| played.get_cell(x, y).unwrap().is_open())
+                };
+                // Prefer moving the mines the solver could not work out, since those are
+                // what it got stuck on.
+                let deduced_mines = solver::deduce(&played).mines;
+                let mut stuck_mines = (0..layout.len())
+                    .filter(|index| {
+                        borders_opened(layout[*index]) && !deduced_mines.contains(&layout[*index])
+                    })
+                    .collect::<Vec<_>>();
+                if stuck_mines.is_empty() {
+                    stuck_mines = (0..layout.len())
+                        .filter(|index| borders_opened(layout[*index]))
+                        .collect();
+                }
+                let mined = layout.iter().copied().collect::<HashSet<_>>();
+                let free_coordinates = possible_coordinates
+                    .iter()
+                    .copied()
+                    .filter(|coordinate| {
+                        !mined.contains(coordinate)
+                            && !played
+                                .get_cell(coordinate.0, coordinate.1)
+                                .unwrap()
+                                .is_open()
+                            && !borders_opened(*coordinate)
+                    })
+                    .collect::<Vec<_>>();
+                let (Some(stuck_mine), Some(free_coordinate)) =
+                    (stuck_mines.choose(rng), free_coordinates.choose(rng))
+                else {
+                    break;
+                };
+                layout[*stuck_mine] = *free_coordinate;
             }
-            self.get_cell_mut(mine_x, mine_y).unwrap().become_mined();
-            self.mined_coordinates.insert((mine_x, mine_y));
         }
+        None
     }
-    #[cfg(feature = "benching")]
-    pub fn generate_mines_with_seed(&mut self, x: u8, y: u8, seed: u64) {
-        if !self.first_open {
-            // DO NOT GENERATE MINES if a cell on the board has already been opened.
-            return;
-        }
-        let mut rng = SmallRng::seed_from_u64(seed);
-        let total_area = self.get_width() as u16 * self.get_height() as u16;
-        let surrounding_coordinates = self
-            .get_surrounding_coordinates(x, y)
-            .collect::<tinyvec::ArrayVec<[_; 8]>>();
-        // In order to make the game more fun, I try and avoid placing mines in the
-        // cells surrounding the cell selected. However, if the number of mines is too
-        // high, this may not be possible, and therefore must be accounted for.
-        let too_many_mines =
-            total_area - (surrounding_coordinates.len() as u16 + 1) < self.mine_count.get();
-        let mut disallowed_coordinates = tinyvec::array_vec!([_; 9] => (x,y));
-        // If we have enough space, we will not place mines in the cells surrounding the
-        // cell specified
-        if !too_many_mines {
-            disallowed_coordinates.extend_from_slice(surrounding_coordinates.as_slice());
-        }
-        let possible_coordinates = (0..self.width.get()).flat_map(|x| {
-            (0..self.height.get()).filter_map(move |y| {
-                if disallowed_coordinates.contains(&(x, y)) {
-                    None
-                } else {
-                    Some((x, y))
-                }
-            })
-        });
-        let mined_coordinates =
-            possible_coordinates.choose_multiple(&mut rng, self.mine_count.get() as usize);
+    /// Places mines on the given coordinates and updates the adjacent mine
+    /// counts around them, after which the layout of the board is final.
+    pub(crate) fn place_mines(&mut self, mined_coordinates: impl IntoIterator<Item = (u16, u16)>) {
         for (mine_x, mine_y) in mined_coordinates {
             for (cell_x, cell_y) in self.get_surrounding_coordinates(mine_x, mine_y) {
                 self.get_cell_mut(cell_x, cell_y)
@@ -340,27 +1058,70 @@ impl Board {
                     .increment_adjacent_mines();
             }
             self.get_cell_mut(mine_x, mine_y).unwrap().become_mined();
-            self.mined_coordinates.insert((mine_x, mine_y));
+            self.mined_cells
+                .insert(coordinate_to_linear(mine_x, mine_y, self.width));
+        }
+        self.first_open = false;
+    }
+    /// Opens a single cell without flooding outwards or checking whether the
+    /// game is over, for restoring a board from a saved layout. Call
+    /// [`Board::update_state`] once every cell has been restored.
+    pub(crate) fn reveal_cell(&mut self, x: u16, y: u16) {
+        if self.is_unopened(x, y) {
+            self.open_single(x, y);
         }
     }
+    /// Works out whether the game has been won or lost from the cells that are
+    /// open.
+    pub(crate) fn update_state(&mut self) {
+        self.state = if self.exploded_count >= self.lives.get() {
+            BoardState::Lost
+        } else if self.unopened_safe_count == 0 {
+            BoardState::Won
+        } else {
+            BoardState::InProgress
+        };
+    }
+    /// Generates the mines as if `(x, y)` was the first cell opened, without
+    /// opening it, so that benchmarks can time opening separately from
+    /// generation.
+    #[cfg(feature = "benching")]
+    pub fn generate_mines_at(&mut self, x: u16, y: u16) {
+        self.generate_mines(x, y);
+    }
 }
 
-const fn coordinate_to_linear(x: u8, y: u8, width: NonZeroU8) -> usize {
+const fn coordinate_to_linear(x: u16, y: u16, width: NonZeroU16) -> usize {
     y as usize * width.get() as usize + x as usize
 }
 
+const fn linear_to_coordinate(index: usize, width: NonZeroU16) -> (u16, u16) {
+    (
+        (index % width.get() as usize) as u16,
+        (index / width.get() as usize) as u16,
+    )
+}
+
 #[cfg(test)]
 mod testing {
     use cell::AdjacentMines;
 
     use super::*;
-    fn create_board(x: u8, y: u8, m: u16) -> Result<Board, BoardError> {
-        Board::create_custom(
-            NonZeroU8::new(x).unwrap(),
-            NonZeroU8::new(y).unwrap(),
-            NonZeroU16::new(m).unwrap(),
+    /// Creates a board with a fixed seed, so that every run of a test plays on
+    /// the same layout.
+    fn create_board(x: u16, y: u16, m: u32) -> Result<Board, BoardError> {
+        Board::create_custom_seeded(
+            NonZeroU16::new(x).unwrap(),
+            NonZeroU16::new(y).unwrap(),
+            NonZeroU32::new(m).unwrap(),
+            0,
         )
     }
+    fn get_any_mine(board: &Board) -> (u16, u16) {
+        let index = board.mined_cells.iter().next().unwrap();
+        let width = board.get_width() as usize;
+        ((index % width) as u16, (index / width) as u16)
+    }
     #[test]
     fn test_board_creation() {
         let board_check = create_board(5, 10, 15);
@@ -382,6 +1143,17 @@ mod testing {
         assert!(board_check.is_err());
     }
     #[test]
+    fn test_board_large() {
+        let mut board = create_board(4096, 3, 6000).unwrap();
+        board.open_cell(4000, 2);
+        assert!(board.get_cell(4000, 2).unwrap().is_open());
+        assert!(board.get_cell(4095, 2).is_some());
+        assert!(matches!(
+            create_board(4097, 2, 1),
+            Err(BoardError::TooLarge { .. })
+        ));
+    }
+    #[test]
     fn test_board_get_cell() {
         let mut board = create_board(5, 5, 5).unwrap();
         for x in 0..5 {
@@ -438,6 +1210,44 @@ mod testing {
         assert!(!board.get_cell(0, 0).unwrap().is_flagged());
     }
     #[test]
+    fn test_board_question_marks() {
+        let mut board = create_board(5, 5, 3).unwrap();
+        board.open_cell(0, 0);
+        let (mine_x, mine_y) = get_any_mine(&board);
+        assert!(board.cycle_mark(mine_x, mine_y).flag_changed);
+        assert_eq!(board.get_remaining_mine_count(), 2);
+        board.cycle_mark(mine_x, mine_y);
+        assert!(board.get_cell(mine_x, mine_y).unwrap().is_question_marked());
+        assert_eq!(board.get_remaining_mine_count(), 3);
+        board.undo();
+        assert!(board.get_cell(mine_x, mine_y).unwrap().is_flagged());
+        assert_eq!(board.get_remaining_mine_count(), 2);
+        board.redo();
+        board.cycle_mark(mine_x, mine_y);
+        assert!(!board.get_cell(mine_x, mine_y).unwrap().is_question_marked());
+        assert_eq!(board.get_remaining_mine_count(), 3);
+    }
+    #[test]
+    fn test_board_chord_question_mark() {
+        let mut board = Board::create_from_text("1*\n11\n..").unwrap();
+        // A question mark does not count towards the flags around the cell
+        board.cycle_mark(1, 0);
+        board.cycle_mark(1, 0);
+        assert!(board.chord_cell(0, 0).is_no_op());
+        // Once the mine is flagged, chording opens the question-marked cells
+        board.cycle_mark(1, 0);
+        board.toggle_flag(1, 0);
+        board.cycle_mark(0, 2);
+        board.cycle_mark(0, 2);
+        board.chord_cell(0, 1);
+        assert!(board.get_cell(0, 2).unwrap().is_open());
+        assert!(matches!(board.get_state(), BoardState::Won));
+        // Undoing the chord puts the question mark back
+        board.undo();
+        assert!(board.get_cell(0, 2).unwrap().is_question_marked());
+        assert_eq!(board.get_cell(1, 2).unwrap().get_mark(), Some(Mark::None));
+    }
+    #[test]
     fn test_board_no_op() {
         let mut board = create_board(5, 5, 5).unwrap();
         board.toggle_flag(0, 0);
@@ -445,11 +1255,506 @@ mod testing {
         assert!(!board.get_cell(0, 0).unwrap().is_flagged());
         board.open_cell(0, 0);
         assert!(board.get_cell(0, 0).unwrap().is_open());
-        let pre_double_check = board.unopened_coordinates.clone();
+        let pre_double_check = board.unopened_cells.clone();
         board.toggle_flag(0, 0);
         assert!(!board.get_cell(0, 0).unwrap().is_flagged());
         board.open_cell(0, 0);
         assert!(board.get_cell(0, 0).unwrap().is_open());
-        assert_eq!(board.unopened_coordinates, pre_double_check);
+        assert_eq!(board.unopened_cells, pre_double_check);
+    }
+    #[test]
+    fn test_board_seeded() {
+        let create_seeded = |seed| {
+            Board::create_custom_seeded(
+                NonZeroU16::new(16).unwrap(),
+                NonZeroU16::new(16).unwrap(),
+                NonZeroU32::new(40).unwrap(),
+                seed,
+            )
+            .unwrap()
+        };
+        let mut board = create_seeded(1234);
+        let mut same_seed = create_seeded(1234);
+        assert_eq!(board.get_seed(), 1234);
+        board.open_cell(3, 7);
+        same_seed.open_cell(3, 7);
+        assert_eq!(board.mined_cells, same_seed.mined_cells);
+        assert_eq!(board.unopened_cells, same_seed.unopened_cells);
+        let mut different_seed = create_seeded(4321);
+        different_seed.open_cell(3, 7);
+        assert_ne!(board.mined_cells, different_seed.mined_cells);
+    }
+    #[test]
+    fn test_board_no_guess() {
+        for seed in 0..10 {
+            let mut board = Board::create_custom_seeded(
+                NonZeroU16::new(9).unwrap(),
+                NonZeroU16::new(9).unwrap(),
+                NonZeroU32::new(10).unwrap(),
+                seed,
+            )
+            .unwrap()
+            .with_generation_mode(GenerationMode::NoGuess);
+            board.open_cell(4, 4);
+            let played = solver::play_without_guessing(&board, 4, 4);
+            assert!(matches!(played.get_state(), BoardState::Won));
+            assert_eq!(board.get_generation_mode(), GenerationMode::NoGuess);
+        }
+    }
+    #[test]
+    fn test_board_no_guess_fallback() {
+        // Only one of the cells around the first click is safe, so finding it always
+        // takes a guess
+        let mut board = create_board(3, 3, 7)
+            .unwrap()
+            .with_generation_mode(GenerationMode::NoGuess);
+        assert_eq!(board.get_generation_mode(), GenerationMode::NoGuess);
+        board.open_cell(1, 1);
+        assert_eq!(board.get_generation_mode(), GenerationMode::Random);
+        assert_eq!(
+            board.new_game().get_generation_mode(),
+            GenerationMode::NoGuess
+        );
+    }
+    #[test]
+    fn test_board_first_click_policy() {
+        let create_full_board = |seed, first_click_policy| {
+            Board::create_custom_seeded(
+                NonZeroU16::new(4).unwrap(),
+                NonZeroU16::new(4).unwrap(),
+                NonZeroU32::new(15).unwrap(),
+                seed,
+            )
+            .unwrap()
+            .with_first_click_policy(first_click_policy)
+        };
+        // With every cell but one mined, the first cell is only certain to be safe
+        // when the policy protects it
+        for seed in 0..8 {
+            let mut board = create_full_board(seed, FirstClickPolicy::SafeCell);
+            board.open_cell(1, 1);
+            assert!(matches!(board.get_state(), BoardState::Won));
+        }
+        assert!((0..8).any(|seed| {
+            let mut board = create_full_board(seed, FirstClickPolicy::Unprotected);
+            board.open_cell(1, 1).hit_mine
+        }));
+        // Only the safe opening policy keeps the neighbours clear as well
+        let mut board = create_board(9, 9, 10).unwrap();
+        assert_eq!(
+            board.get_first_click_policy(),
+            FirstClickPolicy::SafeOpening
+        );
+        board.open_cell(4, 4);
+        assert_eq!(
+            board.get_cell(4, 4).unwrap().adjacent_mines(),
+            Some(AdjacentMines::Zero)
+        );
+        let board = create_full_board(0, FirstClickPolicy::SafeCell);
+        assert_eq!(
+            board.new_game().get_first_click_policy(),
+            FirstClickPolicy::SafeCell
+        );
+    }
+    #[test]
+    fn test_board_new_game() {
+        let board = create_board(7, 8, 9)
+            .unwrap()
+            .with_generation_mode(GenerationMode::NoGuess);
+        let new_board = board.new_game();
+        assert_eq!(new_board.get_width(), 7);
+        assert_eq!(new_board.get_height(), 8);
+        assert_eq!(new_board.get_mine_count(), 9);
+        assert_eq!(new_board.get_generation_mode(), GenerationMode::NoGuess);
+        assert!(new_board.first_open);
+    }
+    #[test]
+    fn test_board_wrapping() {
+        let board = create_board(5, 4, 3)
+            .unwrap()
+            .with_topology(Topology::Wrapping);
+        for y in 0..4 {
+            for x in 0..5 {
+                assert_eq!(board.get_surrounding_coordinates(x, y).count(), 8);
+            }
+        }
+        let corner = board.get_surrounding_coordinates(0, 0).collect::<Vec<_>>();
+        assert!(corner.contains(&(4, 3)));
+        assert!(corner.contains(&(1, 3)));
+        assert!(corner.contains(&(4, 1)));
+        let narrow = create_board(2, 5, 1)
+            .unwrap()
+            .with_topology(Topology::Wrapping);
+        assert_eq!(narrow.get_surrounding_coordinates(0, 0).count(), 5);
+        let new_board = board.new_game();
+        assert_eq!(new_board.get_topology(), Topology::Wrapping);
+    }
+    #[test]
+    fn test_board_wrapping_layout() {
+        // A mine in the corner touches the opposite corners once the edges are joined
+        let board = Board::create_with_mines(
+            NonZeroU16::new(4).unwrap(),
+            NonZeroU16::new(4).unwrap(),
+            &[(0, 0)],
+        )
+        .unwrap();
+        assert_eq!(
+            board.get_cell(3, 3).unwrap().adjacent_mines(),
+            Some(AdjacentMines::Zero)
+        );
+        let board = board.with_topology(Topology::Wrapping);
+        assert_eq!(
+            board.get_cell(3, 3).unwrap().adjacent_mines(),
+            Some(AdjacentMines::One)
+        );
+        assert_eq!(
+            board.get_cell(0, 3).unwrap().adjacent_mines(),
+            Some(AdjacentMines::One)
+        );
+        // A column of mines splits a bounded board in two, but opening a zero on a
+        // wrapping board floods around the other side of it
+        let wall = [(2, 0), (2, 1), (2, 2), (2, 3)];
+        let mut board = Board::create_with_mines(
+            NonZeroU16::new(6).unwrap(),
+            NonZeroU16::new(4).unwrap(),
+            &wall,
+        )
+        .unwrap();
+        assert_eq!(board.open_cell(0, 0).revealed.len(), 8);
+        let mut board = Board::create_with_mines(
+            NonZeroU16::new(6).unwrap(),
+            NonZeroU16::new(4).unwrap(),
+            &wall,
+        )
+        .unwrap()
+        .with_topology(Topology::Wrapping);
+        assert_eq!(board.open_cell(0, 0).revealed.len(), 20);
+        assert!(matches!(board.get_state(), BoardState::Won));
+        // The topology can no longer change once a cell is open
+        let board = board.with_topology(Topology::Bounded);
+        assert_eq!(board.get_topology(), Topology::Wrapping);
+    }
+    #[test]
+    fn test_board_hexagonal() {
+        let board = create_board(5, 4, 3)
+            .unwrap()
+            .with_topology(Topology::Hexagonal);
+        let mut even_row = board.get_surrounding_coordinates(2, 2).collect::<Vec<_>>();
+        even_row.sort_unstable();
+        assert_eq!(even_row, [(1, 1), (1, 2), (1, 3), (2, 1), (2, 3), (3, 2)]);
+        let mut odd_row = board.get_surrounding_coordinates(2, 1).collect::<Vec<_>>();
+        odd_row.sort_unstable();
+        assert_eq!(odd_row, [(1, 1), (2, 0), (2, 2), (3, 0), (3, 1), (3, 2)]);
+        assert_eq!(board.get_surrounding_coordinates(0, 0).count(), 2);
+        assert_eq!(board.get_surrounding_coordinates(4, 1).count(), 3);
+        // Being a neighbour goes both ways
+        for y in 0..4 {
+            for x in 0..5 {
+                for (sur_x, sur_y) in board.get_surrounding_coordinates(x, y) {
+                    assert!(
+                        board
+                            .get_surrounding_coordinates(sur_x, sur_y)
+                            .any(|coordinate| coordinate == (x, y))
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_board_hexagonal_layout() {
+        let mines = [(1, 1), (2, 1), (3, 1), (1, 2), (3, 2), (2, 3), (1, 3)];
+        let mut board = Board::create_with_mines(
+            NonZeroU16::new(5).unwrap(),
+            NonZeroU16::new(5).unwrap(),
+            &mines,
+        )
+        .unwrap()
+        .with_topology(Topology::Hexagonal);
+        // The cell in the middle touches six mines, as many as a hexagon can
+        assert_eq!(
+            board.get_cell(2, 2).unwrap().adjacent_mines(),
+            Some(AdjacentMines::Six)
+        );
+        // The cell above the corner touches no mines on a hexagonal board, unlike on a
+        // square one, so the flood carries on up the side
+        let mut revealed = board.open_cell(4, 4).revealed;
+        revealed.sort_unstable();
+        assert_eq!(revealed, [(3, 3), (3, 4), (4, 2), (4, 3), (4, 4)]);
+    }
+    #[test]
+    fn test_board_move_outcome() {
+        let mut board = create_board(5, 5, 5).unwrap();
+        let outcome = board.open_cell(0, 0);
+        assert_eq!(outcome.revealed.first(), Some(&(0, 0)));
+        assert_eq!(
+            outcome.revealed.len(),
+            25 - board.unopened_cells.iter().count()
+        );
+        assert!(!outcome.hit_mine);
+        assert!(board.open_cell(0, 0).is_no_op());
+        let (mine_x, mine_y) = get_any_mine(&board);
+        let outcome = board.toggle_flag(mine_x, mine_y);
+        assert!(outcome.flag_changed);
+        assert!(outcome.revealed.is_empty());
+        assert!(board.open_cell(mine_x, mine_y).is_no_op());
+        board.toggle_flag(mine_x, mine_y);
+        let outcome = board.open_cell(mine_x, mine_y);
+        assert_eq!(outcome.revealed, [(mine_x, mine_y)]);
+        assert!(outcome.hit_mine);
+    }
+    #[test]
+    fn test_board_iterators() {
+        let mut board = Board::create_from_text("..*\n...\n*..").unwrap();
+        let coordinates: Vec<_> = board
+            .iter_cells()
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        assert_eq!(coordinates.len(), 9);
+        assert_eq!(coordinates[..4], [(0, 0), (1, 0), (2, 0), (0, 1)]);
+        assert_eq!(
+            board
+                .iter_cells()
+                .filter(|(_, cell)| cell.is_mine())
+                .count(),
+            2
+        );
+        let neighbours: Vec<_> = board
+            .iter_neighbours(0, 0)
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        assert_eq!(neighbours.len(), 3);
+        assert!(neighbours.contains(&(1, 1)));
+        assert_eq!(board.iter_neighbours(3, 0).count(), 0);
+        assert_eq!(board.iter_frontier().count(), 0);
+        board.open_cell(0, 0);
+        let frontier: Vec<_> = board
+            .iter_frontier()
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        // The zero in the corner opens the other three cells around it
+        assert_eq!(frontier, [(2, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        board.toggle_flag(2, 0);
+        board.cycle_mark(0, 2);
+        board.cycle_mark(0, 2);
+        let flagged: Vec<_> = board
+            .iter_flagged()
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        assert_eq!(flagged, [(2, 0)]);
+    }
+    #[test]
+    fn test_board_try_moves() {
+        let mut board = Board::create_from_text("..*\n...\n*..").unwrap();
+        assert_eq!(
+            board.try_open_cell(3, 0),
+            Err(MoveError::OutOfBounds { x: 3, y: 0 })
+        );
+        assert_eq!(
+            board.try_toggle_flag(0, 3),
+            Err(MoveError::OutOfBounds { x: 0, y: 3 })
+        );
+        assert!(board.try_toggle_flag(2, 0).unwrap().flag_changed);
+        assert_eq!(
+            board.try_open_cell(2, 0),
+            Err(MoveError::Flagged { x: 2, y: 0 })
+        );
+        // Moves that are allowed but change nothing are still no-ops
+        assert!(board.try_chord_cell(1, 1).unwrap().is_no_op());
+        assert!(board.try_open_cell(0, 2).unwrap().hit_mine);
+        assert_eq!(board.try_open_cell(1, 1), Err(MoveError::GameOver));
+        assert_eq!(board.try_cycle_mark(1, 1), Err(MoveError::GameOver));
+        assert_eq!(
+            MoveError::Flagged { x: 2, y: 0 }.to_string(),
+            "the cell at (2, 0) is flagged"
+        );
+    }
+    #[test]
+    fn test_board_lives() {
+        let board = Board::create_from_text("..*\n...\n*..").unwrap();
+        assert_eq!(board.get_lives(), 1);
+        let mut board = board.with_lives(NonZeroU32::new(2).unwrap());
+        board.open_cell(0, 0);
+        let outcome = board.open_cell(2, 0);
+        assert!(outcome.hit_mine);
+        assert!(matches!(board.get_state(), BoardState::InProgress));
+        assert!(board.get_cell(2, 0).unwrap().is_open());
+        assert_eq!(board.get_remaining_lives(), 1);
+        assert_eq!(board.get_remaining_mine_count(), 1);
+        // The exploded mine counts towards chording as if it were flagged
+        assert!(board.chord_cell(2, 0).is_no_op());
+        assert!(!board.chord_cell(1, 0).is_no_op());
+        board.undo();
+        board.undo();
+        assert_eq!(board.get_remaining_lives(), 2);
+        board.open_cell(2, 0);
+        board.open_cell(0, 2);
+        assert_eq!(board.get_remaining_lives(), 0);
+        assert!(matches!(board.get_state(), BoardState::Lost));
+        // Running out of lives ends the game for good, even if every safe cell is
+        // opened afterwards
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1), (2, 1), (1, 2), (2, 2)] {
+            assert!(board.open_cell(x, y).is_no_op());
+        }
+        assert!(matches!(board.get_state(), BoardState::Lost));
+    }
+    #[test]
+    fn test_board_lives_win() {
+        let mut board = Board::create_from_text("*.\n..")
+            .unwrap()
+            .with_lives(NonZeroU32::new(3).unwrap());
+        board.open_cell(0, 0);
+        for (x, y) in [(1, 0), (0, 1), (1, 1)] {
+            assert!(matches!(board.get_state(), BoardState::InProgress));
+            board.open_cell(x, y);
+        }
+        assert!(matches!(board.get_state(), BoardState::Won));
+        assert_eq!(board.get_remaining_lives(), 2);
+    }
+    #[test]
+    fn test_board_undo_loss() {
+        let mut board = create_board(5, 5, 5).unwrap();
+        board.open_cell(0, 0);
+        let unopened_cells = board.unopened_cells.clone();
+        let (mine_x, mine_y) = get_any_mine(&board);
+        board.open_cell(mine_x, mine_y);
+        assert!(matches!(board.get_state(), BoardState::Lost));
+        assert!(board.undo());
+        assert!(matches!(board.get_state(), BoardState::InProgress));
+        assert!(!board.get_cell(mine_x, mine_y).unwrap().is_open());
+        assert_eq!(board.unopened_cells, unopened_cells);
+        assert!(board.has_used_undo());
+        assert!(board.redo());
+        assert!(matches!(board.get_state(), BoardState::Lost));
+        assert!(!board.can_redo());
+    }
+    #[test]
+    fn test_board_lost_is_final() {
+        let mut board = Board::create_from_text("*..\n...\n...").unwrap();
+        board.open_cell(0, 0);
+        assert!(matches!(board.get_state(), BoardState::Lost));
+        // Opening every safe cell afterwards neither changes the board nor wins
+        let safe_coordinates: Vec<_> = board
+            .iter_cells()
+            .filter(|(_, cell)| !cell.is_mine())
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        for (x, y) in safe_coordinates {
+            assert!(board.open_cell(x, y).is_no_op());
+        }
+        assert!(board.toggle_flag(1, 1).is_no_op());
+        assert!(matches!(board.get_state(), BoardState::Lost));
+    }
+    #[test]
+    fn test_board_undo_first_open() {
+        let mut board = create_board(9, 9, 10).unwrap();
+        board.toggle_flag(8, 8);
+        board.open_cell(4, 4);
+        assert!(!board.can_undo());
+        assert!(!board.undo());
+        // Any other cell is opened on the layout generated around the first click,
+        // which stays open
+        let (x, y) = board
+            .iter_cells()
+            .find(|(_, cell)| !cell.is_open() && !cell.is_flagged())
+            .map(|(coordinate, _)| coordinate)
+            .unwrap();
+        board.open_cell(x, y);
+        assert!(board.get_cell(4, 4).unwrap().is_open());
+        assert!(!board.get_cell(4, 4).unwrap().is_mine());
+        assert!(board.undo());
+        assert!(!board.undo());
+        assert!(board.get_cell(4, 4).unwrap().is_open());
+        assert!(board.get_cell(8, 8).unwrap().is_flagged());
+    }
+    #[test]
+    fn test_board_undo_redo() {
+        let mut board = create_board(5, 5, 5).unwrap();
+        assert!(!board.undo());
+        board.open_cell(0, 0);
+        let (mine_x, mine_y) = get_any_mine(&board);
+        board.toggle_flag(mine_x, mine_y);
+        // Moves that change nothing are not recorded
+        board.open_cell(mine_x, mine_y);
+        assert!(board.undo());
+        assert!(!board.get_cell(mine_x, mine_y).unwrap().is_flagged());
+        // The first open placed the mines, so it cannot be undone
+        assert!(!board.can_undo());
+        assert!(board.get_cell(0, 0).unwrap().is_open());
+        assert!(board.redo());
+        assert!(board.get_cell(mine_x, mine_y).unwrap().is_flagged());
+        assert!(board.undo());
+        // A new move forgets the moves that were undone
+        board.toggle_flag(mine_x, mine_y);
+        assert!(!board.can_redo());
+        assert!(!board.redo());
+    }
+    #[test]
+    fn test_board_serialization() {
+        let mut board = create_board(9, 9, 10).unwrap();
+        board.open_cell(4, 4);
+        let (mine_x, mine_y) = get_any_mine(&board);
+        board.toggle_flag(mine_x, mine_y);
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&board, &mut bytes).unwrap();
+        let mut restored: Board = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(restored.mined_cells, board.mined_cells);
+        assert_eq!(restored.unopened_cells, board.unopened_cells);
+        assert_eq!(restored.get_remaining_mine_count(), 9);
+        assert!(restored.get_cell(mine_x, mine_y).unwrap().is_flagged());
+        assert!(restored.has_mines());
+        assert!(matches!(restored.get_state(), BoardState::InProgress));
+        // The history is kept as well
+        assert!(restored.undo());
+        assert!(!restored.get_cell(mine_x, mine_y).unwrap().is_flagged());
+    }
+    #[test]
+    fn test_board_with_mines() {
+        let create_with_mines = |mined_coordinates: &[(u16, u16)]| {
+            Board::create_with_mines(
+                NonZeroU16::new(4).unwrap(),
+                NonZeroU16::new(3).unwrap(),
+                mined_coordinates,
+            )
+        };
+        let mut board = create_with_mines(&[(0, 0), (3, 2)]).unwrap();
+        assert_eq!(board.get_mine_count(), 2);
+        assert!(board.get_cell(0, 0).unwrap().is_mine());
+        assert_eq!(
+            board.get_cell(1, 1).unwrap().adjacent_mines(),
+            Some(AdjacentMines::One)
+        );
+        // The layout is used as is, even if the first cell opened is next to a mine
+        board.open_cell(1, 0);
+        assert!(board.get_cell(1, 0).unwrap().is_open());
+        assert!(board.get_cell(0, 0).unwrap().is_mine());
+        assert!(matches!(create_with_mines(&[]), Err(BoardError::NoMines)));
+        assert!(matches!(
+            create_with_mines(&[(4, 0)]),
+            Err(BoardError::InvalidMine { x: 4, y: 0 })
+        ));
+        assert!(matches!(
+            create_with_mines(&[(1, 1), (1, 1)]),
+            Err(BoardError::InvalidMine { x: 1, y: 1 })
+        ));
+        let every_cell = (0..4)
+            .flat_map(|x| (0..3).map(move |y| (x, y)))
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            create_with_mines(&every_cell),
+            Err(BoardError::TooManyMines { .. })
+        ));
+    }
+    #[test]
+    fn test_board_from_grid() {
+        let (width, height) = (NonZeroU16::new(3).unwrap(), NonZeroU16::new(2).unwrap());
+        let grid = [false, true, false, false, false, true];
+        let board = Board::create_from_grid(width, height, &grid).unwrap();
+        assert_eq!(board.get_mine_count(), 2);
+        assert!(board.get_cell(1, 0).unwrap().is_mine());
+        assert!(board.get_cell(2, 1).unwrap().is_mine());
+        assert!(matches!(
+            Board::create_from_grid(width, height, &grid[..5]),
+            Err(BoardError::GridSizeMismatch { expected: 6 })
+        ));
     }
 }
@@ -1,12 +1,12 @@
 use std::ops::{Add, AddAssign};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Cell {
     open_state: OpenState,
     mine_state: MineState,
 }
 
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum AdjacentMines {
     #[default]
     Zero = 0,
@@ -68,13 +68,24 @@ impl std::fmt::Display for AdjacentMines {
     }
 }
 
-#[derive(Clone, Debug)]
+/// What the player has marked an unopened cell with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Mark {
+    #[default]
+    None,
+    Flag,
+    /// A reminder that the cell might be a mine, which unlike a flag does not
+    /// stop the cell from being opened.
+    QuestionMark,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 enum OpenState {
     Opened,
-    Unopened { is_flagged: bool },
+    Unopened { mark: Mark },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 enum MineState {
     Mined,
     Safe { adjacent_mines: AdjacentMines },
@@ -84,6 +95,8 @@ enum MineState {
 enum CellEvent {
     Open,
     ToggleFlag,
+    CycleMark,
+    Close(Mark),
     BecomeMined,
     IncrementAdjacentMines,
 }
@@ -91,7 +104,7 @@ enum CellEvent {
 impl Cell {
     pub const fn new() -> Self {
         Cell {
-            open_state: OpenState::Unopened { is_flagged: false },
+            open_state: OpenState::Unopened { mark: Mark::None },
             mine_state: MineState::Safe {
                 adjacent_mines: AdjacentMines::Zero,
             },
@@ -106,8 +119,33 @@ impl Cell {
     pub fn toggle_flag(&mut self) {
         self.cell_transition(CellEvent::ToggleFlag);
     }
+    /// Moves on to the next mark in the order no mark, flag, question mark, as
+    /// right-clicking does in the classic game.
+    pub fn cycle_mark(&mut self) {
+        self.cell_transition(CellEvent::CycleMark);
+    }
+    /// Returns an opened cell to being unopened with the mark it had before it
+    /// was opened, which is only used when undoing a move.
+    pub(crate) fn close(&mut self, mark: Mark) {
+        self.cell_transition(CellEvent::Close(mark));
+    }
+    /// Returns the mark on an unopened cell, or `None` if the cell is open.
+    pub const fn get_mark(&self) -> Option<Mark> {
+        match self.open_state {
+            OpenState::Opened => None,
+            OpenState::Unopened { mark } => Some(mark),
+        }
+    }
     pub const fn is_flagged(&self) -> bool {
-        matches!(self.open_state, OpenState::Unopened { is_flagged: true })
+        matches!(self.open_state, OpenState::Unopened { mark: Mark::Flag })
+    }
+    pub const fn is_question_marked(&self) -> bool {
+        matches!(
+            self.open_state,
+            OpenState::Unopened {
+                mark: Mark::QuestionMark
+            }
+        )
     }
     pub fn become_mined(&mut self) {
         self.cell_transition(CellEvent::BecomeMined);
@@ -128,15 +166,35 @@ impl Cell {
     // Single function to handle all cell state transitions
     fn cell_transition(&mut self, cell_event: CellEvent) {
         match (cell_event, &self.open_state, &self.mine_state) {
+            (CellEvent::Close(mark), OpenState::Opened, _) => {
+                self.open_state = OpenState::Unopened { mark };
+            }
             // If the cell is already opened, we should not do anything to change its state
             (_, OpenState::Opened, _) => (),
-            (CellEvent::Open, OpenState::Unopened { is_flagged: false }, _) => {
+            (
+                CellEvent::Open,
+                OpenState::Unopened {
+                    mark: Mark::None | Mark::QuestionMark,
+                },
+                _,
+            ) => {
                 self.open_state = OpenState::Opened;
             }
-            (CellEvent::ToggleFlag, OpenState::Unopened { is_flagged }, _) => {
-                self.open_state = OpenState::Unopened {
-                    is_flagged: !is_flagged,
+            (CellEvent::ToggleFlag, OpenState::Unopened { mark }, _) => {
+                let mark = if let Mark::Flag = mark {
+                    Mark::None
+                } else {
+                    Mark::Flag
+                };
+                self.open_state = OpenState::Unopened { mark };
+            }
+            (CellEvent::CycleMark, OpenState::Unopened { mark }, _) => {
+                let mark = match mark {
+                    Mark::None => Mark::Flag,
+                    Mark::Flag => Mark::QuestionMark,
+                    Mark::QuestionMark => Mark::None,
                 };
+                self.open_state = OpenState::Unopened { mark };
             }
             (CellEvent::BecomeMined, OpenState::Unopened { .. }, MineState::Safe { .. }) => {
                 self.mine_state = MineState::Mined;
@@ -205,6 +263,42 @@ mod testing {
         assert!(!cell.is_flagged());
     }
     #[test]
+    fn test_cell_cycle_mark() {
+        let mut cell = Cell::new();
+        cell.cycle_mark();
+        assert!(cell.is_flagged());
+        cell.cycle_mark();
+        assert!(!cell.is_flagged());
+        assert!(cell.is_question_marked());
+        cell.toggle_flag();
+        assert!(cell.is_flagged());
+        cell.cycle_mark();
+        cell.cycle_mark();
+        assert!(!cell.is_flagged());
+        assert!(!cell.is_question_marked());
+    }
+    #[test]
+    fn test_cell_open_question_marked() {
+        let mut cell = Cell::new();
+        cell.cycle_mark();
+        cell.cycle_mark();
+        cell.open();
+        assert!(cell.is_open());
+        assert!(!cell.is_question_marked());
+    }
+    #[test]
+    fn test_cell_close() {
+        let mut cell = Cell::new();
+        cell.open();
+        assert_eq!(cell.get_mark(), None);
+        cell.close(Mark::None);
+        assert!(!cell.is_open());
+        assert_eq!(cell.get_mark(), Some(Mark::None));
+        cell.open();
+        cell.close(Mark::QuestionMark);
+        assert!(cell.is_question_marked());
+    }
+    #[test]
     fn test_cell_become_mined() {
         let mut cell = Cell::new();
         cell.become_mined();
@@ -0,0 +1,79 @@
+use std::num::{NonZeroU16, NonZeroU32};
+
+use chrono::{Datelike, NaiveDate};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::board::{Board, FirstClickPolicy, GenerationMode};
+
+/// The board that every player gets on a given calendar date. Both the layout
+/// of the mines and the cell the first click goes on are derived from the
+/// date, so everyone starts from exactly the same position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct DailyChallenge {
+    date: NaiveDate,
+}
+
+impl DailyChallenge {
+    const WIDTH: NonZeroU16 = NonZeroU16::new(16).unwrap();
+    const HEIGHT: NonZeroU16 = NonZeroU16::new(16).unwrap();
+    const MINE_COUNT: NonZeroU32 = NonZeroU32::new(40).unwrap();
+    pub const fn create(date: NaiveDate) -> Self {
+        Self { date }
+    }
+    /// The challenge for the current date in the local time zone.
+    pub fn create_today() -> Self {
+        Self::create(chrono::Local::now().date_naive())
+    }
+    pub const fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+    pub fn get_seed(&self) -> u64 {
+        self.date.num_days_from_ce() as u64
+    }
+    /// Returns the cell the first click of the challenge goes on.
+    pub fn get_first_click(&self) -> (u16, u16) {
+        // Seeded apart from the layout, which is generated from the plain seed
+        let mut rng = StdRng::seed_from_u64(!self.get_seed());
+        (
+            rng.random_range(0..Self::WIDTH.get()),
+            rng.random_range(0..Self::HEIGHT.get()),
+        )
+    }
+    /// Creates the challenge's board, ready for the first click to be made on
+    /// [`DailyChallenge::get_first_click`].
+    pub fn create_board(&self) -> Board {
+        Board::create_custom_seeded(Self::WIDTH, Self::HEIGHT, Self::MINE_COUNT, self.get_seed())
+            .unwrap()
+            .with_generation_mode(GenerationMode::Random)
+            .with_first_click_policy(FirstClickPolicy::SafeOpening)
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::core::board::BoardState;
+    fn play_first_click(challenge: DailyChallenge) -> Board {
+        let mut board = challenge.create_board();
+        let (x, y) = challenge.get_first_click();
+        board.open_cell(x, y);
+        board
+    }
+    #[test]
+    fn test_daily_same_board() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+        let board = play_first_click(DailyChallenge::create(date));
+        let same_day = play_first_click(DailyChallenge::create(date));
+        assert!(matches!(board.get_state(), BoardState::InProgress));
+        assert_eq!(board.analyse(), same_day.analyse());
+        let coordinates = (0..16).flat_map(|y| (0..16).map(move |x| (x, y)));
+        assert!(coordinates.clone().all(|(x, y)| {
+            board.get_cell(x, y).unwrap().is_mine() == same_day.get_cell(x, y).unwrap().is_mine()
+        }));
+        let next_day = play_first_click(DailyChallenge::create(date.succ_opt().unwrap()));
+        assert!(coordinates.clone().any(|(x, y)| {
+            board.get_cell(x, y).unwrap().is_mine() != next_day.get_cell(x, y).unwrap().is_mine()
+        }));
+    }
+}
@@ -0,0 +1,300 @@
+//! Reading and writing boards as text grids, and mine layouts in the
+//! Minesweeper Board Format (MBF).
+//!
+//! MBF is the only binary layout format supported. The other formats that
+//! carry layouts, such as RMV and AVF replays, hold them alongside a whole
+//! recorded game and are not read or written here.
+
+use std::num::NonZeroU16;
+
+use thiserror::Error;
+
+use crate::core::{
+    board::{Board, BoardError},
+    cell::Mark,
+};
+
+/// Why a board could not be read from a text grid or a binary layout.
+#[derive(Debug, Error)]
+pub enum FormatError {
+    /// There were no rows to read.
+    #[error("there are no rows to read")]
+    Empty,
+    /// Row `y` is not as long as the first row.
+    #[error("row {y} is not as long as the first row")]
+    UnevenRow { y: usize },
+    /// The character at `(x, y)` is not part of the text format.
+    #[error("'{character}' at ({x}, {y}) is not a cell")]
+    InvalidCharacter { x: u16, y: u16, character: char },
+    /// The opened cell at `(x, y)` has a number that does not match the mines
+    /// around it.
+    #[error("the number at ({x}, {y}) does not match the mines around it")]
+    IncorrectNumber { x: u16, y: u16 },
+    /// The layout has more rows or columns than a board can hold.
+    #[error("the layout has too many rows or columns")]
+    TooLarge,
+    /// The binary layout ended before all of its mines were read, or carried
+    /// data after them.
+    #[error("the layout does not hold exactly as many mines as it says")]
+    InvalidLength,
+    /// The board described breaks the rules that
+    /// [`Board::create_with_mines`] enforces.
+    #[error(transparent)]
+    InvalidBoard(#[from] BoardError),
+}
+
+impl Board {
+    /// Reads a board from a text grid with one line per row, where each
+    /// character is one cell:
+    ///
+    /// - `.` is an unopened safe cell, and `*` an unopened mine.
+    /// - `F` is a flagged mine, and `f` a flag placed on a safe cell.
+    /// - `Q` is a question-marked mine, and `q` a question-marked safe cell.
+    /// - `0` to `8` is an opened cell, showing how many mines surround it.
+    /// - `X` is a mine that has been opened, which means the game was lost.
+    ///
+    /// Blank lines and whitespace at the ends of a line are ignored.
+    pub fn create_from_text(text: &str) -> Result<Self, FormatError> {
+        let rows = text
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .map(|row| row.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let Some(first_row) = rows.first() else {
+            return Err(FormatError::Empty);
+        };
+        if let Some(y) = rows.iter().position(|row| row.len() != first_row.len()) {
+            return Err(FormatError::UnevenRow { y });
+        }
+        let width = u16::try_from(first_row.len()).map_err(|_| FormatError::TooLarge)?;
+        let height = u16::try_from(rows.len()).map_err(|_| FormatError::TooLarge)?;
+        let cell_at = |x: u16, y: u16| rows[y as usize][x as usize];
+        let coordinates = (0..height).flat_map(|y| (0..width).map(move |x| (x, y)));
+        if let Some((x, y)) = coordinates.clone().find(|(x, y)| {
+            !matches!(
+                cell_at(*x, *y),
+                '.' | '*' | 'F' | 'f' | 'Q' | 'q' | 'X' | '0'..='8'
+            )
+        }) {
+            return Err(FormatError::InvalidCharacter {
+                x,
+                y,
+                character: cell_at(x, y),
+            });
+        }
+        let mined_coordinates = coordinates
+            .clone()
+            .filter(|(x, y)| matches!(cell_at(*x, *y), '*' | 'F' | 'Q' | 'X'))
+            .collect::<Vec<_>>();
+        let mut board = Board::create_with_mines(
+            NonZeroU16::new(width).unwrap(),
+            NonZeroU16::new(height).unwrap(),
+            &mined_coordinates,
+        )?;
+        for (x, y) in coordinates {
+            match cell_at(x, y) {
+                'F' | 'f' => {
+                    board.flag(x, y);
+                }
+                // Cycling goes from no mark to a flag and then to a question mark
+                'Q' | 'q' => {
+                    board.cycle(x, y);
+                    board.cycle(x, y);
+                }
+                'X' => board.reveal_cell(x, y),
+                digit @ '0'..='8' => {
+                    let adjacent_mines = board.get_cell(x, y).unwrap().adjacent_mines();
+                    if adjacent_mines.map(u8::from) != digit.to_digit(10).map(|digit| digit as u8) {
+                        return Err(FormatError::IncorrectNumber { x, y });
+                    }
+                    board.reveal_cell(x, y);
+                }
+                _ => (),
+            }
+        }
+        board.update_state();
+        Ok(board)
+    }
+    /// Writes the board as a text grid, in the format read by
+    /// [`Board::create_from_text`]. Until the first cell is opened there are no
+    /// mines, so every cell is written as unopened and safe.
+    pub fn to_text(&self) -> String {
+        let mut text =
+            String::with_capacity((self.get_width() as usize + 1) * self.get_height() as usize);
+        for y in 0..self.get_height() {
+            for x in 0..self.get_width() {
+                let cell = self.get_cell(x, y).unwrap();
+                // Only unopened cells have a mark
+                let character = match (cell.get_mark(), cell.is_mine()) {
+                    (None, true) => 'X',
+                    (None, false) => {
+                        char::from_digit(u8::from(cell.adjacent_mines().unwrap()) as u32, 10)
+                            .unwrap()
+                    }
+                    (Some(Mark::Flag), true) => 'F',
+                    (Some(Mark::Flag), false) => 'f',
+                    (Some(Mark::QuestionMark), true) => 'Q',
+                    (Some(Mark::QuestionMark), false) => 'q',
+                    (Some(Mark::None), true) => '*',
+                    (Some(Mark::None), false) => '.',
+                };
+                text.push(character);
+            }
+            text.push('\n');
+        }
+        text
+    }
+    /// Reads a mine layout in the Minesweeper Board Format (MBF) used by
+    /// Minesweeper Arbiter and other clones: the width and height as one byte
+    /// each, the mine count as a big-endian `u16`, and then the `x` and `y`
+    /// coordinates of every mine as one byte each. The board has no cells
+    /// opened.
+    pub fn create_from_mbf(bytes: &[u8]) -> Result<Self, FormatError> {
+        let [width, height, mine_high, mine_low, mine_bytes @ ..] = bytes else {
+            return Err(FormatError::InvalidLength);
+        };
+        let mine_count = u16::from_be_bytes([*mine_high, *mine_low]);
+        if mine_bytes.len() != mine_count as usize * 2 {
+            return Err(FormatError::InvalidLength);
+        }
+        let mined_coordinates = mine_bytes
+            .chunks_exact(2)
+            .map(|mine| (mine[0] as u16, mine[1] as u16))
+            .collect::<Vec<_>>();
+        Ok(Board::create_with_mines(
+            NonZeroU16::new(*width as u16).ok_or(BoardError::InvalidBoardSize)?,
+            NonZeroU16::new(*height as u16).ok_or(BoardError::InvalidBoardSize)?,
+            &mined_coordinates,
+        )?)
+    }
+    /// Writes the mine layout in the Minesweeper Board Format, as read by
+    /// [`Board::create_from_mbf`]. Returns `None` if the mines have not been
+    /// placed yet, or if the board is too large for the format to describe.
+    pub fn to_mbf(&self) -> Option<Vec<u8>> {
+        if !self.has_mines() {
+            return None;
+        }
+        let width = u8::try_from(self.get_width()).ok()?;
+        let height = u8::try_from(self.get_height()).ok()?;
+        let mine_count = u16::try_from(self.get_mine_count()).ok()?;
+        let mut bytes = Vec::with_capacity(4 + mine_count as usize * 2);
+        bytes.extend([width, height]);
+        bytes.extend(mine_count.to_be_bytes());
+        for y in 0..height {
+            for x in 0..width {
+                if self.get_cell(x as u16, y as u16).unwrap().is_mine() {
+                    bytes.extend([x, y]);
+                }
+            }
+        }
+        Some(bytes)
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::core::board::BoardState;
+    #[test]
+    fn test_text_round_trip() {
+        let text = "\
+            1*2F1\n\
+            11f11\n\
+            ..000\n";
+        let board = Board::create_from_text(text).unwrap();
+        assert_eq!(board.get_width(), 5);
+        assert_eq!(board.get_height(), 3);
+        assert_eq!(board.get_mine_count(), 2);
+        assert!(board.get_cell(1, 0).unwrap().is_mine());
+        assert!(board.get_cell(3, 0).unwrap().is_flagged());
+        assert!(!board.get_cell(2, 1).unwrap().is_mine());
+        assert!(board.get_cell(2, 1).unwrap().is_flagged());
+        assert!(board.get_cell(4, 2).unwrap().is_open());
+        assert!(matches!(board.get_state(), BoardState::InProgress));
+        assert_eq!(board.to_text(), text);
+        // Question marks are kept as well, and do not count as flags
+        let text = "Q1\nq1\n";
+        let board = Board::create_from_text(text).unwrap();
+        assert!(board.get_cell(0, 0).unwrap().is_mine());
+        assert!(board.get_cell(0, 0).unwrap().is_question_marked());
+        assert!(board.get_cell(0, 1).unwrap().is_question_marked());
+        assert_eq!(board.get_remaining_mine_count(), 1);
+        assert_eq!(board.to_text(), text);
+    }
+    #[test]
+    fn test_text_states() {
+        let board = Board::create_from_text("X1\n11").unwrap();
+        assert!(matches!(board.get_state(), BoardState::Lost));
+        let board = Board::create_from_text("*1\n11").unwrap();
+        assert!(matches!(board.get_state(), BoardState::Won));
+    }
+    #[test]
+    fn test_text_invalid() {
+        assert!(matches!(
+            Board::create_from_text(""),
+            Err(FormatError::Empty)
+        ));
+        assert!(matches!(
+            Board::create_from_text("*.\n."),
+            Err(FormatError::UnevenRow { y: 1 })
+        ));
+        assert!(matches!(
+            Board::create_from_text("*?"),
+            Err(FormatError::InvalidCharacter {
+                x: 1,
+                y: 0,
+                character: '?'
+            })
+        ));
+        assert!(matches!(
+            Board::create_from_text("*2\n.."),
+            Err(FormatError::IncorrectNumber { x: 1, y: 0 })
+        ));
+        assert_eq!(
+            Board::create_from_text("*2\n..").unwrap_err().to_string(),
+            "the number at (1, 0) does not match the mines around it"
+        );
+        assert!(matches!(
+            Board::create_from_text("..\n.."),
+            Err(FormatError::InvalidBoard(BoardError::NoMines))
+        ));
+        assert!(matches!(
+            Board::create_from_text("**\n**"),
+            Err(FormatError::InvalidBoard(BoardError::TooManyMines { .. }))
+        ));
+    }
+    #[test]
+    fn test_mbf_round_trip() {
+        let bytes = [3, 2, 0, 2, 0, 0, 2, 1];
+        let board = Board::create_from_mbf(&bytes).unwrap();
+        assert_eq!(board.get_width(), 3);
+        assert_eq!(board.get_height(), 2);
+        assert!(board.get_cell(0, 0).unwrap().is_mine());
+        assert!(board.get_cell(2, 1).unwrap().is_mine());
+        assert_eq!(board.to_mbf().unwrap(), bytes);
+        assert!(matches!(
+            Board::create_from_mbf(&[3, 2, 0, 2, 0, 0]),
+            Err(FormatError::InvalidLength)
+        ));
+        assert!(matches!(
+            Board::create_from_mbf(&[3, 2, 0, 2, 0, 0, 0, 0]),
+            Err(FormatError::InvalidBoard(BoardError::InvalidMine {
+                x: 0,
+                y: 0
+            }))
+        ));
+        assert!(matches!(
+            Board::create_from_mbf(&[3, 2, 0, 1, 3, 0]),
+            Err(FormatError::InvalidBoard(BoardError::InvalidMine {
+                x: 3,
+                y: 0
+            }))
+        ));
+    }
+    #[test]
+    fn test_mbf_before_mines() {
+        let board = Board::create_beginner();
+        assert!(board.to_mbf().is_none());
+    }
+}
@@ -0,0 +1,930 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::core::{
+    board::{Board, BoardState},
+    cell::AdjacentMines,
+};
+
+/// Upper bound on how many partial layouts are explored while enumerating a
+/// single group of cells, so that a pathological frontier cannot stall the
+/// solver.
+const ENUMERATION_BUDGET: usize = 250_000;
+
+/// Cells whose contents can be deduced from what the player can see.
+#[derive(Clone, Debug, Default)]
+pub struct Deductions {
+    pub safe: Vec<(u16, u16)>,
+    pub mines: Vec<(u16, u16)>,
+}
+
+/// The chance of every unopened cell of a board being a mine, given what the
+/// player can see.
+#[derive(Clone, Debug)]
+pub struct MineProbabilities {
+    width: u16,
+    probabilities: Box<[Option<f64>]>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Knowledge {
+    Unknown,
+    Safe,
+    Mine,
+}
+
+/// A revealed number, stating that exactly `mines` of `cells` are mined.
+#[derive(Clone, Debug)]
+struct Constraint {
+    cells: Vec<usize>,
+    mines: usize,
+}
+
+#[derive(Debug)]
+struct Solver<'a> {
+    board: &'a Board,
+    knowledge: Vec<Knowledge>,
+    constraints: Vec<Constraint>,
+}
+
+/// A group of unknown cells that are linked together by the numbers touching
+/// them, along with every way the mines could be laid out over it.
+#[derive(Debug)]
+struct Component {
+    cells: Vec<usize>,
+    /// The layouts grouped by how many mines they use, or `None` if there were
+    /// too many to enumerate.
+    layouts: Option<BTreeMap<usize, Layouts>>,
+}
+
+/// How many layouts of a component use a given number of mines, and how many
+/// of those layouts place a mine on each of its cells.
+#[derive(Clone, Debug)]
+struct Layouts {
+    count: f64,
+    cell_mines: Vec<f64>,
+}
+
+#[derive(Debug)]
+struct Enumeration<'a> {
+    constraints: Vec<(&'a Constraint, Vec<usize>)>,
+    constraints_by_cell: Vec<Vec<usize>>,
+    assigned_mines: Vec<usize>,
+    unassigned_cells: Vec<usize>,
+    assignment: Vec<bool>,
+    layouts: BTreeMap<usize, Layouts>,
+    budget: usize,
+}
+
+/// Finds every unopened cell of `board` that is provably safe or provably
+/// mined, using single-number rules, rules for pairs of overlapping numbers,
+/// the total mine count, and finally an enumeration of every layout of the
+/// frontier.
+///
+/// Flags are not trusted, so a flagged cell is treated like any other unopened
+/// cell. This means a flagged cell can show up in [`Deductions::safe`], which
+/// tells the caller that the flag is wrong.
+pub fn deduce(board: &Board) -> Deductions {
+    let mut solver = Solver::new(board);
+    solver.run();
+    solver.into_deductions()
+}
+
+/// Calculates the exact probability of every unopened cell of `board` being a
+/// mine, taking into account both the revealed numbers and the total number of
+/// mines left. As with [`deduce`], flags are not trusted.
+///
+/// Returns `None` if the frontier has too many possible layouts to be counted
+/// exactly.
+pub fn get_mine_probabilities(board: &Board) -> Option<MineProbabilities> {
+    let mut solver = Solver::new(board);
+    solver.run();
+    solver.simplify_constraints();
+    let components = solver.get_components();
+    let layouts = components
+        .iter()
+        .map(|component| component.layouts.as_ref())
+        .collect::<Option<Vec<_>>>()?;
+    let unknown_cells = solver
+        .knowledge
+        .iter()
+        .filter(|knowledge| **knowledge == Knowledge::Unknown)
+        .count();
+    let interior_cells = unknown_cells - components.iter().map(|c| c.cells.len()).sum::<usize>();
+    let remaining_mines = solver.get_remaining_mines();
+    // Each component's layout counts are scaled down by its largest count, which
+    // keeps the products below from overflowing without changing any ratios.
+    let distributions = layouts
+        .iter()
+        .map(|layouts| {
+            let max_count = layouts
+                .values()
+                .map(|layouts| layouts.count)
+                .fold(0.0, f64::max);
+            let mut distribution = vec![0.0; layouts.keys().max().map_or(0, |max| max + 1)];
+            for (mines, layouts) in layouts.iter() {
+                distribution[*mines] = layouts.count / max_count;
+            }
+            (distribution, max_count)
+        })
+        .collect::<Vec<_>>();
+    let interior_weights = get_interior_weights(interior_cells, remaining_mines);
+    let interior_weight =
+        |frontier_mines: usize| interior_weights.get(frontier_mines).copied().unwrap_or(0.0);
+    let total_distribution = convolve(distributions.iter().map(|(d, _)| d.as_slice()));
+    let total_weight = total_distribution
+        .iter()
+        .enumerate()
+        .map(|(mines, weight)| weight * interior_weight(mines))
+        .sum::<f64>();
+    if total_weight == 0.0 {
+        return None;
+    }
+
+    let mut probabilities = solver
+        .knowledge
+        .iter()
+        .map(|knowledge| match knowledge {
+            Knowledge::Safe => Some(0.0),
+            Knowledge::Mine => Some(1.0),
+            Knowledge::Unknown => None,
+        })
+        .collect::<Box<[_]>>();
+    for (index, component) in components.iter().enumerate() {
+        let other_distribution = convolve(
+            distributions
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, (d, _))| d.as_slice()),
+        );
+        let max_count = distributions[index].1;
+        let mut cell_weights = vec![0.0; component.cells.len()];
+        for (mines, layouts) in layouts[index].iter() {
+            let weight = other_distribution
+                .iter()
+                .enumerate()
+                .map(|(other_mines, weight)| weight * interior_weight(mines + other_mines))
+                .sum::<f64>();
+            for (cell_weight, cell_mines) in cell_weights.iter_mut().zip(layouts.cell_mines.iter())
+            {
+                *cell_weight += cell_mines / max_count * weight;
+            }
+        }
+        for (cell, cell_weight) in component.cells.iter().zip(cell_weights) {
+            probabilities[*cell] = Some(cell_weight / total_weight);
+        }
+    }
+    if interior_cells > 0 {
+        let interior_mines = total_distribution
+            .iter()
+            .enumerate()
+            .map(|(mines, weight)| {
+                weight * interior_weight(mines) * remaining_mines.saturating_sub(mines) as f64
+            })
+            .sum::<f64>();
+        let probability = interior_mines / interior_cells as f64 / total_weight;
+        for probability_slot in probabilities.iter_mut().filter(|p| p.is_none()) {
+            *probability_slot = Some(probability);
+        }
+    }
+    // Opened cells do not have a probability
+    let width = board.get_width() as usize;
+    for (cell, probability) in probabilities.iter_mut().enumerate() {
+        let (x, y) = ((cell % width) as u16, (cell / width) as u16);
+        if board.get_cell(x, y).unwrap().is_open() {
+            *probability = None;
+        }
+    }
+    Some(MineProbabilities {
+        width: board.get_width(),
+        probabilities,
+    })
+}
+
+/// Plays `board` starting from `(x, y)`, only ever opening cells that the
+/// solver has proven to be safe, and returns the board where it got stuck (or
+/// won).
+pub(crate) fn play_without_guessing(board: &Board, x: u16, y: u16) -> Board {
+    let mut board = board.clone();
+    board.open_cell(x, y);
+    while let BoardState::InProgress = board.get_state() {
+        let deductions = deduce(&board);
+        if deductions.safe.is_empty() {
+            break;
+        }
+        for (safe_x, safe_y) in deductions.safe {
+            board.open_cell(safe_x, safe_y);
+        }
+    }
+    board
+}
+
+/// Plays a board that nobody has touched yet like a player who knows where
+/// the mines are would, opening every cell the solver proves safe and, when it
+/// gets stuck, guessing the safe cell that looks the least likely to be a mine.
+/// Returns how many guesses it took to win. The first click is not counted, as
+/// every game needs one.
+pub(crate) fn count_forced_guesses(board: &Board) -> u32 {
+    let mut board = board.clone();
+    let coordinates: Vec<_> = (0..board.get_height())
+        .flat_map(|y| (0..board.get_width()).map(move |x| (x, y)))
+        .collect();
+    let is_safe = |board: &Board, (x, y): (u16, u16)| {
+        let cell = board.get_cell(x, y).unwrap();
+        !cell.is_mine() && !cell.is_open()
+    };
+    // Start on an opening if there is one, since that is what the first click
+    // usually lands on
+    let first_click = coordinates
+        .iter()
+        .copied()
+        .find(|&(x, y)| board.get_cell(x, y).unwrap().adjacent_mines() == Some(AdjacentMines::Zero))
+        .or_else(|| {
+            coordinates
+                .iter()
+                .copied()
+                .find(|&cell| is_safe(&board, cell))
+        });
+    let Some((x, y)) = first_click else {
+        return 0;
+    };
+    board.open_cell(x, y);
+    let mut guesses = 0;
+    while let BoardState::InProgress = board.get_state() {
+        let deductions = deduce(&board);
+        if !deductions.safe.is_empty() {
+            for (safe_x, safe_y) in deductions.safe {
+                board.open_cell(safe_x, safe_y);
+            }
+            continue;
+        }
+        guesses += 1;
+        let probabilities = get_mine_probabilities(&board);
+        let probability = |(x, y): (u16, u16)| {
+            probabilities
+                .as_ref()
+                .and_then(|probabilities| probabilities.get(x, y))
+                .unwrap_or(1.0)
+        };
+        let Some((x, y)) = coordinates
+            .iter()
+            .copied()
+            .filter(|&cell| is_safe(&board, cell))
+            .min_by(|&a, &b| probability(a).total_cmp(&probability(b)))
+        else {
+            break;
+        };
+        board.open_cell(x, y);
+    }
+    guesses
+}
+
+impl<'a> Solver<'a> {
+    fn new(board: &'a Board) -> Self {
+        let width = board.get_width() as usize;
+        let mut knowledge = vec![Knowledge::Unknown; width * board.get_height() as usize];
+        let mut constraints = Vec::new();
+        for y in 0..board.get_height() {
+            for x in 0..board.get_width() {
+                let cell = board.get_cell(x, y).unwrap();
+                if !cell.is_open() {
+                    continue;
+                }
+                // An opened mine is one the player has already stepped on
+                let Some(adjacent_mines) = cell.adjacent_mines() else {
+                    knowledge[y as usize * width + x as usize] = Knowledge::Mine;
+                    continue;
+                };
+                knowledge[y as usize * width + x as usize] = Knowledge::Safe;
+                let mut cells = board
+                    .get_surrounding_coordinates(x, y)
+                    .filter(|(x, y)| !board.get_cell(*x, *y).unwrap().is_open())
+                    .map(|(x, y)| y as usize * width + x as usize)
+                    .collect::<Vec<_>>();
+                if cells.is_empty() {
+                    continue;
+                }
+                cells.sort_unstable();
+                constraints.push(Constraint {
+                    cells,
+                    mines: u8::from(adjacent_mines) as usize,
+                });
+            }
+        }
+        Self {
+            board,
+            knowledge,
+            constraints,
+        }
+    }
+    fn run(&mut self) {
+        while self.apply_single_rules()
+            || self.apply_pair_rules()
+            || self.apply_mine_count()
+            || self.apply_enumeration()
+        {}
+    }
+    fn mark(&mut self, cells: &[usize], knowledge: Knowledge) -> bool {
+        let mut progress = false;
+        for &cell in cells {
+            if self.knowledge[cell] == Knowledge::Unknown {
+                self.knowledge[cell] = knowledge;
+                progress = true;
+            }
+        }
+        progress
+    }
+    /// Removes cells that are already known from every constraint, dropping
+    /// the constraints that have been fully resolved.
+    fn simplify_constraints(&mut self) {
+        let knowledge = &self.knowledge;
+        for constraint in self.constraints.iter_mut() {
+            let known_mines = constraint
+                .cells
+                .iter()
+                .filter(|cell| knowledge[**cell] == Knowledge::Mine)
+                .count();
+            constraint.mines -= known_mines;
+            constraint
+                .cells
+                .retain(|cell| knowledge[*cell] == Knowledge::Unknown);
+        }
+        self.constraints
+            .retain(|constraint| !constraint.cells.is_empty());
+    }
+    /// A number whose unknown neighbours all have to be safe (or all have to be
+    /// mines) resolves those neighbours.
+    fn apply_single_rules(&mut self) -> bool {
+        self.simplify_constraints();
+        let mut progress = false;
+        for constraint in std::mem::take(&mut self.constraints) {
+            if constraint.mines == 0 {
+                progress |= self.mark(&constraint.cells, Knowledge::Safe);
+            } else if constraint.mines == constraint.cells.len() {
+                progress |= self.mark(&constraint.cells, Knowledge::Mine);
+            }
+            self.constraints.push(constraint);
+        }
+        progress
+    }
+    /// Compares every pair of overlapping numbers. If the difference in their
+    /// mine counts can only be explained by one side of the overlap, then that
+    /// side is entirely mined and the other side is entirely safe.
+    fn apply_pair_rules(&mut self) -> bool {
+        self.simplify_constraints();
+        let mut constraints_by_cell = HashMap::<usize, Vec<usize>>::new();
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            for cell in constraint.cells.iter() {
+                constraints_by_cell.entry(*cell).or_default().push(index);
+            }
+        }
+        let mut progress = false;
+        for (a_index, a) in self.constraints.iter().enumerate() {
+            let mut neighbours = a
+                .cells
+                .iter()
+                .flat_map(|cell| constraints_by_cell[cell].iter().copied())
+                .filter(|b_index| *b_index != a_index)
+                .collect::<Vec<_>>();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+            for b in neighbours
+                .into_iter()
+                .map(|b_index| &self.constraints[b_index])
+            {
+                if b.mines < a.mines {
+                    continue;
+                }
+                let only_b = b
+                    .cells
+                    .iter()
+                    .filter(|cell| a.cells.binary_search(cell).is_err())
+                    .copied()
+                    .collect::<Vec<_>>();
+                let only_a = a
+                    .cells
+                    .iter()
+                    .filter(|cell| b.cells.binary_search(cell).is_err())
+                    .copied()
+                    .collect::<Vec<_>>();
+                if b.mines - a.mines == only_b.len() {
+                    // Every mine of `a` has to lie in the overlap for `b` to fit
+                    // its extra mines into the cells only it touches.
+                    for cell in only_b {
+                        if self.knowledge[cell] == Knowledge::Unknown {
+                            self.knowledge[cell] = Knowledge::Mine;
+                            progress = true;
+                        }
+                    }
+                    for cell in only_a {
+                        if self.knowledge[cell] == Knowledge::Unknown {
+                            self.knowledge[cell] = Knowledge::Safe;
+                            progress = true;
+                        }
+                    }
+                    if progress {
+                        return true;
+                    }
+                }
+            }
+        }
+        progress
+    }
+    /// Uses the total number of mines on the board, which settles every
+    /// unknown cell once either all mines or all safe cells have been found.
+    fn apply_mine_count(&mut self) -> bool {
+        let known_mines = self
+            .knowledge
+            .iter()
+            .filter(|knowledge| **knowledge == Knowledge::Mine)
+            .count();
+        let unknown_cells = self
+            .knowledge
+            .iter()
+            .enumerate()
+            .filter(|(_, knowledge)| **knowledge == Knowledge::Unknown)
+            .map(|(cell, _)| cell)
+            .collect::<Vec<_>>();
+        if unknown_cells.is_empty() {
+            return false;
+        }
+        let remaining_mines = (self.board.get_mine_count() as usize).saturating_sub(known_mines);
+        if remaining_mines == 0 {
+            self.mark(&unknown_cells, Knowledge::Safe)
+        } else if remaining_mines == unknown_cells.len() {
+            self.mark(&unknown_cells, Knowledge::Mine)
+        } else {
+            false
+        }
+    }
+    fn get_remaining_mines(&self) -> usize {
+        let known_mines = self
+            .knowledge
+            .iter()
+            .filter(|knowledge| **knowledge == Knowledge::Mine)
+            .count();
+        (self.board.get_mine_count() as usize).saturating_sub(known_mines)
+    }
+    /// Splits the constraints into groups that share no cells with each other,
+    /// and enumerates the layouts of each group.
+    fn get_components(&self) -> Vec<Component> {
+        let mut constraints_by_cell = HashMap::<usize, Vec<usize>>::new();
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            for cell in constraint.cells.iter() {
+                constraints_by_cell.entry(*cell).or_default().push(index);
+            }
+        }
+        let mut visited = vec![false; self.constraints.len()];
+        let mut components = Vec::new();
+        for start in 0..self.constraints.len() {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            // Visiting the constraints breadth-first keeps the cells of neighbouring
+            // constraints close together, so constraints are completed early during the
+            // enumeration and dead ends are pruned sooner.
+            let mut queue = VecDeque::from([start]);
+            let mut constraint_indices = Vec::new();
+            let mut cells = Vec::new();
+            while let Some(index) = queue.pop_front() {
+                constraint_indices.push(index);
+                for cell in self.constraints[index].cells.iter() {
+                    if !cells.contains(cell) {
+                        cells.push(*cell);
+                    }
+                    for neighbour in constraints_by_cell[cell].iter() {
+                        if !visited[*neighbour] {
+                            visited[*neighbour] = true;
+                            queue.push_back(*neighbour);
+                        }
+                    }
+                }
+            }
+            let layouts = Enumeration::new(
+                &cells,
+                constraint_indices
+                    .into_iter()
+                    .map(|index| &self.constraints[index]),
+            )
+            .run(ENUMERATION_BUDGET);
+            components.push(Component { cells, layouts });
+        }
+        components
+    }
+    /// Enumerates every layout of the frontier, and combines it with the total
+    /// mine count. Cells that are safe (or mined) in every possible layout are
+    /// resolved.
+    fn apply_enumeration(&mut self) -> bool {
+        self.simplify_constraints();
+        let components = self.get_components();
+        let unknown_cells = self
+            .knowledge
+            .iter()
+            .filter(|knowledge| **knowledge == Knowledge::Unknown)
+            .count();
+        let interior_cells =
+            unknown_cells - components.iter().map(|c| c.cells.len()).sum::<usize>();
+        let remaining_mines = self.get_remaining_mines();
+        let mine_counts = components
+            .iter()
+            .map(Component::get_possible_mine_counts)
+            .collect::<Vec<_>>();
+        // A total number of frontier mines is feasible as long as the rest of the
+        // mines fit into the cells away from the frontier.
+        let is_feasible = |frontier_mines: usize| {
+            frontier_mines <= remaining_mines && remaining_mines - frontier_mines <= interior_cells
+        };
+        let mut progress = false;
+        for (index, component) in components.iter().enumerate() {
+            let Some(layouts) = &component.layouts else {
+                continue;
+            };
+            let other_sums = get_reachable_sums(
+                mine_counts
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(_, counts)| counts.as_slice()),
+            );
+            let feasible_layouts = layouts
+                .iter()
+                .filter(|(mines, _)| {
+                    other_sums
+                        .iter()
+                        .enumerate()
+                        .any(|(sum, reachable)| *reachable && is_feasible(sum + **mines))
+                })
+                .map(|(_, layouts)| layouts)
+                .collect::<Vec<_>>();
+            for (position, cell) in component.cells.iter().enumerate() {
+                if feasible_layouts
+                    .iter()
+                    .all(|layouts| layouts.cell_mines[position] == 0.0)
+                {
+                    progress |= self.mark(&[*cell], Knowledge::Safe);
+                } else if feasible_layouts
+                    .iter()
+                    .all(|layouts| layouts.cell_mines[position] == layouts.count)
+                {
+                    progress |= self.mark(&[*cell], Knowledge::Mine);
+                }
+            }
+        }
+        if interior_cells > 0 {
+            let feasible_sums = get_reachable_sums(mine_counts.iter().map(Vec::as_slice))
+                .into_iter()
+                .enumerate()
+                .filter(|(sum, reachable)| *reachable && is_feasible(*sum))
+                .map(|(sum, _)| remaining_mines - sum)
+                .collect::<Vec<_>>();
+            let interior_knowledge = if feasible_sums.iter().all(|mines| *mines == 0) {
+                Some(Knowledge::Safe)
+            } else if feasible_sums.iter().all(|mines| *mines == interior_cells) {
+                Some(Knowledge::Mine)
+            } else {
+                None
+            };
+            if let Some(knowledge) = interior_knowledge {
+                let interior = (0..self.knowledge.len())
+                    .filter(|cell| {
+                        self.knowledge[*cell] == Knowledge::Unknown
+                            && !components.iter().any(|c| c.cells.contains(cell))
+                    })
+                    .collect::<Vec<_>>();
+                progress |= self.mark(&interior, knowledge);
+            }
+        }
+        progress
+    }
+    fn into_deductions(self) -> Deductions {
+        let width = self.board.get_width() as usize;
+        let mut deductions = Deductions::default();
+        for (cell, knowledge) in self.knowledge.into_iter().enumerate() {
+            let (x, y) = ((cell % width) as u16, (cell / width) as u16);
+            if self.board.get_cell(x, y).unwrap().is_open() {
+                continue;
+            }
+            match knowledge {
+                Knowledge::Safe => deductions.safe.push((x, y)),
+                Knowledge::Mine => deductions.mines.push((x, y)),
+                Knowledge::Unknown => (),
+            }
+        }
+        deductions
+    }
+}
+
+impl Component {
+    /// Returns every number of mines this component could hold. A component
+    /// that could not be enumerated is assumed to be able to hold any number.
+    fn get_possible_mine_counts(&self) -> Vec<usize> {
+        match &self.layouts {
+            Some(layouts) => layouts.keys().copied().collect(),
+            None => (0..=self.cells.len()).collect(),
+        }
+    }
+}
+
+impl<'a> Enumeration<'a> {
+    fn new(cells: &[usize], constraints: impl Iterator<Item = &'a Constraint>) -> Self {
+        let mut constraints_by_cell = vec![Vec::new(); cells.len()];
+        let constraints = constraints
+            .enumerate()
+            .map(|(index, constraint)| {
+                let positions = constraint
+                    .cells
+                    .iter()
+                    .map(|cell| cells.iter().position(|other| other == cell).unwrap())
+                    .collect::<Vec<_>>();
+                for position in positions.iter() {
+                    constraints_by_cell[*position].push(index);
+                }
+                (constraint, positions)
+            })
+            .collect::<Vec<_>>();
+        Self {
+            assigned_mines: vec![0; constraints.len()],
+            unassigned_cells: constraints
+                .iter()
+                .map(|(_, positions)| positions.len())
+                .collect(),
+            constraints,
+            constraints_by_cell,
+            assignment: Vec::with_capacity(cells.len()),
+            layouts: BTreeMap::new(),
+            budget: 0,
+        }
+    }
+    /// Enumerates every layout, giving up and returning `None` once more than
+    /// `budget` partial layouts have been explored.
+    fn run(mut self, budget: usize) -> Option<BTreeMap<usize, Layouts>> {
+        self.budget = budget;
+        self.search().then_some(self.layouts)
+    }
+    fn search(&mut self) -> bool {
+        if self.budget == 0 {
+            return false;
+        }
+        self.budget -= 1;
+        let position = self.assignment.len();
+        if position == self.constraints_by_cell.len() {
+            let mines = self.assignment.iter().filter(|mined| **mined).count();
+            let layouts = self.layouts.entry(mines).or_insert_with(|| Layouts {
+                count: 0.0,
+                cell_mines: vec![0.0; position],
+            });
+            layouts.count += 1.0;
+            for (cell_mines, mined) in layouts.cell_mines.iter_mut().zip(self.assignment.iter()) {
+                if *mined {
+                    *cell_mines += 1.0;
+                }
+            }
+            return true;
+        }
+        for mined in [false, true] {
+            let fits = self.constraints_by_cell[position].iter().all(|index| {
+                let mines = self.assigned_mines[*index] + mined as usize;
+                let target = self.constraints[*index].0.mines;
+                // The cell itself is still counted as unassigned here
+                mines <= target && mines + self.unassigned_cells[*index] > target
+            });
+            if !fits {
+                continue;
+            }
+            for index in self.constraints_by_cell[position].iter() {
+                self.assigned_mines[*index] += mined as usize;
+                self.unassigned_cells[*index] -= 1;
+            }
+            self.assignment.push(mined);
+            let finished = self.search();
+            self.assignment.pop();
+            for index in self.constraints_by_cell[position].iter() {
+                self.assigned_mines[*index] -= mined as usize;
+                self.unassigned_cells[*index] += 1;
+            }
+            if !finished {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl MineProbabilities {
+    /// Returns the probability of the cell at `(x, y)` being a mine, or `None`
+    /// if the cell is open or out of bounds.
+    pub fn get(&self, x: u16, y: u16) -> Option<f64> {
+        if x >= self.width {
+            return None;
+        }
+        self.probabilities
+            .get(y as usize * self.width as usize + x as usize)
+            .copied()
+            .flatten()
+    }
+}
+
+/// Returns, for every number of mines on the frontier, how many ways the rest
+/// of the mines can be spread over the cells away from the frontier. The
+/// weights are scaled so that the largest is one, since the binomial
+/// coefficients involved easily exceed what a float can hold.
+fn get_interior_weights(interior_cells: usize, remaining_mines: usize) -> Vec<f64> {
+    let mut log_weights = vec![f64::NEG_INFINITY; remaining_mines + 1];
+    // Starts from the fewest frontier mines possible, where the interior holds as
+    // many mines as it can, and works upwards using the ratio between consecutive
+    // binomial coefficients.
+    let first_mines = remaining_mines.saturating_sub(interior_cells);
+    let mut log_weight = 0.0;
+    for (frontier_mines, slot) in log_weights.iter_mut().enumerate().skip(first_mines) {
+        *slot = log_weight;
+        let interior_mines = (remaining_mines - frontier_mines) as f64;
+        log_weight += interior_mines.ln() - (interior_cells as f64 - interior_mines + 1.0).ln();
+    }
+    let max_log_weight = log_weights
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    log_weights
+        .into_iter()
+        .map(|log_weight| (log_weight - max_log_weight).exp())
+        .collect()
+}
+
+/// Combines independent distributions over mine counts into the distribution
+/// of their total.
+fn convolve<'a>(distributions: impl Iterator<Item = &'a [f64]>) -> Vec<f64> {
+    let mut total = vec![1.0];
+    for distribution in distributions {
+        let mut next = vec![0.0; total.len() + distribution.len().saturating_sub(1)];
+        for (mines, weight) in total.iter().enumerate() {
+            for (other_mines, other_weight) in distribution.iter().enumerate() {
+                next[mines + other_mines] += weight * other_weight;
+            }
+        }
+        total = next;
+    }
+    total
+}
+
+/// Returns which totals can be reached by picking one count from each of the
+/// given lists, indexed by the total.
+fn get_reachable_sums<'a>(counts: impl Iterator<Item = &'a [usize]>) -> Vec<bool> {
+    let mut reachable = vec![true];
+    for counts in counts {
+        let max_count = counts.iter().max().copied().unwrap_or(0);
+        let mut next = vec![false; reachable.len() + max_count];
+        for (sum, _) in reachable.iter().enumerate().filter(|(_, r)| **r) {
+            for count in counts {
+                next[sum + count] = true;
+            }
+        }
+        reachable = next;
+    }
+    reachable
+}
+
+#[cfg(test)]
+mod testing {
+    use std::num::{NonZeroU16, NonZeroU32};
+
+    use super::*;
+    fn create_board(x: u16, y: u16, m: u32, seed: u64) -> Board {
+        Board::create_custom_seeded(
+            NonZeroU16::new(x).unwrap(),
+            NonZeroU16::new(y).unwrap(),
+            NonZeroU32::new(m).unwrap(),
+            seed,
+        )
+        .unwrap()
+    }
+    #[test]
+    fn test_solver_mine_count() {
+        let mut board = create_board(8, 1, 1, 0);
+        board.place_mines([(6, 0)]);
+        board.open_cell(2, 0);
+        let deductions = deduce(&board);
+        // (6, 0) is forced by the number next to it, after which the total mine count
+        // makes (7, 0) safe even though no number touches it.
+        assert_eq!(deductions.mines, vec![(6, 0)]);
+        assert_eq!(deductions.safe, vec![(7, 0)]);
+    }
+    #[test]
+    fn test_solver_fifty_fifty() {
+        let mut board = create_board(5, 2, 2, 0);
+        board.place_mines([(0, 0), (4, 0)]);
+        board.open_cell(2, 1);
+        let deductions = deduce(&board);
+        assert!(deductions.safe.is_empty());
+        assert!(deductions.mines.is_empty());
+    }
+    #[test]
+    fn test_solver_ignores_flags() {
+        let mut board = create_board(8, 1, 1, 0);
+        board.place_mines([(6, 0)]);
+        board.open_cell(2, 0);
+        board.toggle_flag(7, 0);
+        let deductions = deduce(&board);
+        assert_eq!(deductions.mines, vec![(6, 0)]);
+        assert_eq!(deductions.safe, vec![(7, 0)]);
+    }
+    /// Tries every way of placing the mines on the unopened cells of a 5x4
+    /// board, returning the unopened cells along with the layouts (as bitmasks
+    /// over those cells) that agree with every revealed number.
+    fn get_consistent_layouts(board: &Board) -> (Vec<(u16, u16)>, Vec<u32>) {
+        let unknown = (0..5)
+            .flat_map(|x| (0..4).map(move |y| (x, y)))
+            .filter(|(x, y)| !board.get_cell(*x, *y).unwrap().is_open())
+            .collect::<Vec<_>>();
+        let layouts = (0u32..(1 << unknown.len()))
+            .filter(|layout| layout.count_ones() == board.get_mine_count())
+            .filter(|layout| {
+                let is_mined = |coordinate: (u16, u16)| {
+                    unknown
+                        .iter()
+                        .position(|other| *other == coordinate)
+                        .is_some_and(|index| layout & (1 << index) != 0)
+                };
+                (0..5).all(|x| {
+                    (0..4).all(|y| {
+                        let cell = board.get_cell(x, y).unwrap();
+                        !cell.is_open()
+                            || board
+                                .get_surrounding_coordinates(x, y)
+                                .filter(|coordinate| is_mined(*coordinate))
+                                .count()
+                                == u8::from(cell.adjacent_mines().unwrap()) as usize
+                    })
+                })
+            })
+            .collect();
+        (unknown, layouts)
+    }
+    #[test]
+    fn test_solver_matches_brute_force() {
+        for seed in 0..25 {
+            let mut board = create_board(5, 4, 5, seed);
+            board.open_cell(0, 0);
+            let (unknown, layouts) = get_consistent_layouts(&board);
+            let mut expected_safe = (0..unknown.len())
+                .filter(|index| layouts.iter().all(|layout| layout & (1 << index) == 0))
+                .map(|index| unknown[index])
+                .collect::<Vec<_>>();
+            let mut expected_mines = (0..unknown.len())
+                .filter(|index| layouts.iter().all(|layout| layout & (1 << index) != 0))
+                .map(|index| unknown[index])
+                .collect::<Vec<_>>();
+            let mut deductions = deduce(&board);
+            for coordinates in [
+                &mut expected_safe,
+                &mut expected_mines,
+                &mut deductions.safe,
+                &mut deductions.mines,
+            ] {
+                coordinates.sort_unstable();
+            }
+            assert_eq!(deductions.safe, expected_safe);
+            assert_eq!(deductions.mines, expected_mines);
+        }
+    }
+    #[test]
+    fn test_probabilities_matches_brute_force() {
+        for seed in 0..25 {
+            let mut board = create_board(5, 4, 5, seed);
+            board.open_cell(0, 0);
+            let (unknown, layouts) = get_consistent_layouts(&board);
+            let probabilities = get_mine_probabilities(&board).unwrap();
+            for (index, (x, y)) in unknown.into_iter().enumerate() {
+                let mined_layouts = layouts
+                    .iter()
+                    .filter(|layout| *layout & (1 << index) != 0)
+                    .count();
+                let expected = mined_layouts as f64 / layouts.len() as f64;
+                let probability = probabilities.get(x, y).unwrap();
+                assert!((probability - expected).abs() < 1e-9);
+            }
+        }
+    }
+    #[test]
+    fn test_probabilities_before_first_open() {
+        let board = create_board(30, 16, 99, 0);
+        let probabilities = get_mine_probabilities(&board).unwrap();
+        for x in 0..30 {
+            for y in 0..16 {
+                let probability = probabilities.get(x, y).unwrap();
+                assert!((probability - 99.0 / 480.0).abs() < 1e-9);
+            }
+        }
+    }
+    #[test]
+    fn test_probabilities_fifty_fifty() {
+        let mut board = create_board(5, 2, 2, 0);
+        board.place_mines([(0, 0), (4, 0)]);
+        board.open_cell(2, 1);
+        let probabilities = get_mine_probabilities(&board).unwrap();
+        for (x, y) in [(0, 0), (0, 1), (4, 0), (4, 1)] {
+            assert!((probabilities.get(x, y).unwrap() - 0.5).abs() < 1e-9);
+        }
+        assert_eq!(probabilities.get(2, 1), None);
+    }
+}
@@ -38,6 +38,9 @@ pub trait Screen: std::fmt::Debug + Send + Sync {
     fn subscription(&self) -> Option<Subscription<Message>> {
         None
     }
+    /// Called when the app is about to close, so that the screen can save
+    /// anything that would otherwise be lost.
+    fn on_close(&mut self) {}
 }
 
 pub type ArcLock<T> = Arc<RwLock<T>>;
@@ -78,6 +81,7 @@ impl Screen for Application {
                 None
             }
             AppMessage::CloseApp => {
+                self.screen.on_close();
                 self.clear_cache().unwrap_or_else(|e| {
                     eprintln!("Failed to clear cache: {e}");
                 });
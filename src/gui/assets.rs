@@ -117,6 +117,21 @@ macro_rules! create_image_assets {
                     }
                 });
 
+                pub static QUESTION_MARK: LazyLock<Vec<u8>> = LazyLock::new(|| {
+                    let cache_result = get_data_from_cache(std::path::Path::new(concat!("images/",stringify!($name), "/QuestionMark.", $extension)));
+                    match cache_result {
+                        Ok(data) => data,
+                        Err(CacheError::NotFound) => {
+                            if let Err(e) = create_cache() {
+                                panic!("Failed to create cache: {e:?}");
+                            }
+                            get_data_from_cache(std::path::Path::new(concat!("images/",stringify!($name), "/QuestionMark.", $extension)))
+                                .expect("Failed to read QuestionMark from cache")
+                        }
+                        Err(e) => panic!("Failed to read QuestionMark from cache: {e:?}"),
+                    }
+                });
+
                 pub static INCORRECT_FLAG: LazyLock<Vec<u8>> = LazyLock::new(|| {
                     let cache_result = get_data_from_cache(std::path::Path::new(concat!("images/",stringify!($name), "/IncorrectFlag.", $extension)));
                     match cache_result {
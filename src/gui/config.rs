@@ -5,11 +5,30 @@ use iced::{
     widget::button::{Status as ButtonStatus, Style as ButtonStyle},
 };
 
+use crate::FirstClickPolicy;
+
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct Config {
     pub game_theme: GameTheme,
     pub menu_theme: MenuTheme,
     pub scale_factor: f32,
+    /// Whether right-clicking cycles through a question mark after the flag.
+    #[serde(default)]
+    pub question_marks: bool,
+    /// How much of the board around the first cell opened is kept free of
+    /// mines in new games.
+    #[serde(default)]
+    pub first_click_policy: FirstClickPolicy,
+    /// Whether mines that a number gives away are flagged automatically.
+    #[serde(default)]
+    pub auto_flag: bool,
+    /// Whether the cells around a number whose flags are all placed are
+    /// opened automatically.
+    #[serde(default)]
+    pub auto_open: bool,
+    /// Which mouse buttons chord on a number, on top of the middle button.
+    #[serde(default)]
+    pub chord_style: ChordStyle,
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize, PartialEq, Clone)]
@@ -36,6 +55,26 @@ pub enum MenuTheme {
     NineX,
 }
 
+#[derive(Debug, serde::Deserialize, serde::Serialize, PartialEq, Clone, Copy, Default)]
+pub enum ChordStyle {
+    /// Only the middle button chords.
+    #[default]
+    MiddleButton,
+    /// Pressing the left and right buttons together chords once either is
+    /// released.
+    BothButtons,
+    /// Left-clicking an opened number chords on it.
+    LeftClick,
+}
+
+impl ChordStyle {
+    pub const ALL: &'static [ChordStyle] = &[
+        ChordStyle::MiddleButton,
+        ChordStyle::BothButtons,
+        ChordStyle::LeftClick,
+    ];
+}
+
 #[derive(Debug)]
 pub enum MenuButtonStyle {
     Primary,
@@ -198,12 +237,27 @@ impl Display for MenuTheme {
     }
 }
 
+impl Display for ChordStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ChordStyle::MiddleButton => "Middle button",
+            ChordStyle::BothButtons => "Left and right buttons",
+            ChordStyle::LeftClick => "Left-click on numbers",
+        })
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             game_theme: GameTheme::SimpleLight,
             menu_theme: MenuTheme::Light,
             scale_factor: 1.0,
+            question_marks: false,
+            first_click_policy: FirstClickPolicy::SafeOpening,
+            auto_flag: false,
+            auto_open: false,
+            chord_style: ChordStyle::MiddleButton,
         }
     }
 }
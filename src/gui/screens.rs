@@ -26,5 +26,6 @@ screen_macro!(
     [game, Game],
     [about, About],
     [custom_setup, CustomSetup],
-    [leaderboard, Leaderboard]
+    [leaderboard, Leaderboard],
+    [replay, Replay]
 );
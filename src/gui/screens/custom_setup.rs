@@ -1,18 +1,21 @@
 ﻿use std::{
-    num::{NonZeroU8, NonZeroU16},
+    num::{NonZeroU16, NonZeroU32},
     sync::Arc,
 };
 
 use iced::{Element, Task, widget as GuiWidget};
 
 use super::{AppMessage, Game, GameSelection, Message as SuperMessage};
-use crate::{ArcLock, Board, BoardError, Config, Screen};
+use crate::{ArcLock, Board, BoardError, Config, GenerationMode, Screen, Topology};
 #[derive(Debug)]
 pub struct CustomSetup {
     config: ArcLock<Config>,
     width_string: String,
     height_string: String,
     mines_string: String,
+    no_guess: bool,
+    topology: Topology,
+    lives_mode: bool,
     error_message: Option<Box<str>>,
 }
 
@@ -22,16 +25,26 @@ pub enum Message {
     HeightChanged(String),
     WidthChanged(String),
     MinesChanged(String),
+    NoGuessToggled(bool),
+    TopologySelected(Topology),
+    LivesToggled(bool),
     Submit,
 }
 
 impl CustomSetup {
+    /// The largest width and height of a custom game. The game screen builds
+    /// a widget for every cell, so it cannot keep up with boards anywhere near
+    /// as large as [`Board::MAX_SIZE`].
+    const MAX_SIZE: u16 = 128;
     pub fn build(config: ArcLock<Config>) -> Self {
         Self {
             config,
             width_string: String::new(),
             height_string: String::new(),
             mines_string: String::new(),
+            no_guess: false,
+            topology: Topology::Bounded,
+            lives_mode: false,
             error_message: None,
         }
     }
@@ -44,7 +57,7 @@ fn remove_non_digits(input: &str) -> String {
 
 fn validate_numerical_input<T>(input: &str, max_length: usize) -> String
 where
-    T: Into<usize> + std::str::FromStr,
+    T: Into<u64> + std::str::FromStr,
 {
     if input.len() > max_length {
         return input[0..max_length].to_string();
@@ -78,22 +91,34 @@ impl Screen for CustomSetup {
                 .map(SuperMessage::App),
             ),
             Message::WidthChanged(new_value) => {
-                self.width_string = validate_numerical_input::<u8>(&new_value, 2);
+                self.width_string = validate_numerical_input::<u16>(&new_value, 3);
                 None
             }
             Message::HeightChanged(new_value) => {
-                self.height_string = validate_numerical_input::<u8>(&new_value, 2);
+                self.height_string = validate_numerical_input::<u16>(&new_value, 3);
                 None
             }
             Message::MinesChanged(new_value) => {
-                self.mines_string = validate_numerical_input::<u16>(&new_value, 4);
+                self.mines_string = validate_numerical_input::<u32>(&new_value, 5);
+                None
+            }
+            Message::NoGuessToggled(no_guess) => {
+                self.no_guess = no_guess;
+                None
+            }
+            Message::TopologySelected(topology) => {
+                self.topology = topology;
+                None
+            }
+            Message::LivesToggled(lives_mode) => {
+                self.lives_mode = lives_mode;
                 None
             }
             Message::Submit => {
                 let (width_parsed, height_parsed, mine_parsed) = match (
-                    self.width_string.parse::<u8>(),
-                    self.height_string.parse::<u8>(),
-                    self.mines_string.parse::<u16>(),
+                    self.width_string.parse::<u16>(),
+                    self.height_string.parse::<u16>(),
+                    self.mines_string.parse::<u32>(),
                 ) {
                     (Ok(r), Ok(c), Ok(m)) => (r, c, m),
                     _ => {
@@ -104,9 +129,9 @@ impl Screen for CustomSetup {
                     }
                 };
                 let (width, height, mines) = match (
-                    NonZeroU8::new(width_parsed),
-                    NonZeroU8::new(height_parsed),
-                    NonZeroU16::new(mine_parsed),
+                    NonZeroU16::new(width_parsed),
+                    NonZeroU16::new(height_parsed),
+                    NonZeroU32::new(mine_parsed),
                 ) {
                     (Some(r), Some(c), Some(m)) => (r, c, m),
                     _ => {
@@ -114,6 +139,16 @@ impl Screen for CustomSetup {
                         return None;
                     }
                 };
+                if width.get() > Self::MAX_SIZE || height.get() > Self::MAX_SIZE {
+                    self.error_message = Some(
+                        format!(
+                            "Board too large! Rows and columns can be at most {} in the game.",
+                            Self::MAX_SIZE
+                        )
+                        .into(),
+                    );
+                    return None;
+                }
                 let board = match Board::create_custom(width, height, mines) {
                     Ok(board) => board,
                     Err(BoardError::InvalidBoardSize) => {
@@ -121,13 +156,34 @@ impl Screen for CustomSetup {
                             Some("Invalid board size! Rows and columns cannot both be one.".into());
                         return None;
                     }
+                    Err(BoardError::TooLarge { .. }) => {
+                        unreachable!("The game's own size limit is below the board's")
+                    }
                     Err(BoardError::TooManyMines { max_mines }) => {
                         self.error_message = Some(
                             format!("Too many mines! Maximum for the given rows and columns is {max_mines}.").into(),
                         );
                         return None;
                     }
+                    Err(
+                        BoardError::NoMines
+                        | BoardError::InvalidMine { .. }
+                        | BoardError::GridSizeMismatch { .. },
+                    ) => unreachable!("A board without a given layout cannot have invalid mines"),
                 };
+                let board = board
+                    .with_generation_mode(if self.no_guess {
+                        GenerationMode::NoGuess
+                    } else {
+                        GenerationMode::Random
+                    })
+                    .with_topology(self.topology)
+                    .with_first_click_policy(config.read().unwrap().first_click_policy)
+                    .with_lives(if self.lives_mode {
+                        Game::LIVES_MODE_LIVES
+                    } else {
+                        NonZeroU32::MIN
+                    });
                 Some(
                     Task::perform(async move { Game::build(config, board) }, move |item| {
                         Arc::new(Box::new(item) as Box<dyn Screen>)
@@ -168,6 +224,26 @@ impl Screen for CustomSetup {
             .spacing(10)
             .align_y(iced::Center);
 
+        let no_guess_checkbox = GuiWidget::checkbox(self.no_guess)
+            .label("No guessing")
+            .on_toggle(|no_guess| SuperMessage::CustomSetup(Message::NoGuessToggled(no_guess)))
+            .font(menu_theme.default_font())
+            .text_size(menu_theme.default_text_size());
+        let lives_checkbox = GuiWidget::checkbox(self.lives_mode)
+            .label(format!("{} lives", Game::LIVES_MODE_LIVES))
+            .on_toggle(|lives_mode| SuperMessage::CustomSetup(Message::LivesToggled(lives_mode)))
+            .font(menu_theme.default_font())
+            .text_size(menu_theme.default_text_size());
+        let topology_text = menu_theme.text("Board shape:");
+        let topology_picker = GuiWidget::pick_list(Topology::ALL, Some(self.topology), |topology| {
+            SuperMessage::CustomSetup(Message::TopologySelected(topology))
+        })
+        .font(menu_theme.default_font())
+        .text_size(menu_theme.default_text_size());
+        let topology_row = GuiWidget::row![topology_text, topology_picker]
+            .align_y(iced::Center)
+            .spacing(10);
+
         let submit_button = menu_theme
             .button(menu_theme.text("Submit"), crate::MenuButtonStyle::Primary)
             .on_press(SuperMessage::CustomSetup(Message::Submit));
@@ -183,6 +259,9 @@ impl Screen for CustomSetup {
 
         let content = GuiWidget::column![
             input_content,
+            no_guess_checkbox,
+            lives_checkbox,
+            topology_row,
             GuiWidget::space().height(10),
             error_message,
             buttons
@@ -1,23 +1,150 @@
 ﻿use std::{
-    num::{NonZeroU8, NonZeroU16},
+    num::NonZeroU32,
+    path::PathBuf,
     sync::Arc,
-    time::{Instant, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
-use iced::{Element, Subscription, Task, widget as GuiWidget, widget::svg as GuiSvg};
+use iced::{Element, Subscription, Task, mouse, widget as GuiWidget, widget::svg as GuiSvg};
 
-use super::{AppMessage, Leaderboard, MainMenu, Message as SuperMessage};
-use crate::{ArcLock, Board, BoardState, Cell, Config, GameTheme, Screen, core::cell};
+use super::{
+    AppMessage, Leaderboard, MainMenu, Message as SuperMessage, Replay,
+    leaderboard::FinishedGame,
+    replay::{RecordedAction, Recording},
+};
+use crate::{
+    Application, ArcLock, Board, BoardState, Cell, ChordStyle, Config, GameTheme, Screen, Topology,
+    core::{
+        analysis::BoardAnalysis,
+        cell,
+        daily::DailyChallenge,
+        solver::{self, MineProbabilities},
+    },
+};
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    OpenCell(u8, u8),
-    ToggleFlag(u8, u8),
-    ChordCell(u8, u8),
+    OpenCell(u16, u16),
+    ToggleFlag(u16, u16),
+    ChordCell(u16, u16),
+    CellEntered(u16, u16),
+    CellExited(u16, u16),
+    /// The pointer moved onto the gap at the start of the given row of a
+    /// hexagonal board.
+    GapEntered(u16),
+    GapExited(u16),
+    ButtonPressed(mouse::Button),
+    ButtonReleased(mouse::Button),
     ResetGame,
+    Undo,
+    Redo,
     TimeUpdate(Instant),
+    /// The analysis of the board with the given seed has finished.
+    BoardAnalysed(u64, Option<BoardAnalysis>),
     Back,
     SaveTime,
+    WatchReplay,
+    ProbabilitiesToggled(bool),
+}
+
+/// The version of the saved game format, which is bumped whenever the format
+/// changes in a way that older saves can no longer be read.
+const SAVED_GAME_VERSION: u32 = 5;
+
+/// A game in progress, saved when the app closes so that it can be continued.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct SavedGame {
+    #[serde(rename = "v")]
+    version: u32,
+    #[serde(rename = "b")]
+    board: Board,
+    #[serde(rename = "t")]
+    elapsed_time: Duration,
+    #[serde(rename = "r")]
+    recording: Recording,
+    #[serde(rename = "c", default)]
+    clicks: ClickCounts,
+    #[serde(rename = "y", default)]
+    daily: Option<DailyAttempt>,
+    #[serde(rename = "a", default)]
+    assisted: bool,
+}
+
+/// A game of the daily challenge. Only the first game of each day's challenge
+/// is ranked, as later ones already know the board.
+#[derive(Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct DailyAttempt {
+    #[serde(rename = "c")]
+    challenge: DailyChallenge,
+    #[serde(rename = "r")]
+    ranked: bool,
+}
+
+/// How many times each kind of click was made during a game, counting clicks
+/// that did nothing as well.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct ClickCounts {
+    #[serde(rename = "l")]
+    pub left: u32,
+    #[serde(rename = "r")]
+    pub right: u32,
+    #[serde(rename = "c")]
+    pub chord: u32,
+}
+
+impl ClickCounts {
+    pub const fn total(&self) -> u32 {
+        self.left + self.right + self.chord
+    }
+}
+
+/// The numbers that a game is judged by alongside its time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct Statistics {
+    #[serde(rename = "b")]
+    pub three_bv: u32,
+    #[serde(rename = "c")]
+    pub clicks: ClickCounts,
+}
+
+impl Statistics {
+    pub fn get_3bv_per_second(&self, time: Duration) -> f64 {
+        self.three_bv as f64 / time.as_secs_f64().max(0.001)
+    }
+    /// Returns the index of efficiency (IOE), which is the 3BV divided by how
+    /// many clicks were made, so that a game with no wasted clicks scores at
+    /// least one.
+    pub fn get_efficiency(&self) -> f64 {
+        self.three_bv as f64 / self.clicks.total().max(1) as f64
+    }
+    /// Returns whether the game was played without ever right-clicking, which
+    /// is known as no-flag (NF) play.
+    pub const fn is_no_flag(&self) -> bool {
+        self.clicks.right == 0
+    }
+}
+
+/// The mouse buttons that were pressed on the board and are still held down.
+/// Cells are only opened or chorded on once a button is released.
+#[derive(Copy, Clone, Debug, Default)]
+struct HeldButtons {
+    left: bool,
+    right: bool,
+    middle: bool,
+    /// Whether the buttons held make up a chord, in which case releasing one of
+    /// them chords instead of opening the cell.
+    chording: bool,
 }
 
 #[derive(Debug)]
@@ -27,19 +154,263 @@ pub struct Game {
     start_time: Instant,
     current_time: Instant,
     end_time: Option<SystemTime>,
+    recording: Recording,
+    show_probabilities: bool,
+    probabilities: Option<MineProbabilities>,
+    clicks: ClickCounts,
+    /// The board's 3BV, worked out once the mines have been placed.
+    three_bv: Option<u32>,
+    analysis: Option<BoardAnalysis>,
+    /// Whether the board is being analysed in the background.
+    analysing: bool,
+    daily: Option<DailyAttempt>,
+    /// Whether the assists have made a move for the player this game.
+    assisted: bool,
+    held_buttons: HeldButtons,
+    /// The cell under the pointer, which is the one that a press acts on.
+    hovered_cell: Option<(u16, u16)>,
+    /// The row whose gap on a hexagonal board is under the pointer, which
+    /// counts as the first cell of the row.
+    hovered_gap: Option<u16>,
 }
 
 impl Game {
+    /// How many lives a game has when lives mode is turned on.
+    pub const LIVES_MODE_LIVES: NonZeroU32 = NonZeroU32::new(3).unwrap();
     pub fn build(config: ArcLock<Config>, board: Board) -> Self {
         let game_start = Instant::now();
+        let recording = Recording::create(&board);
         Self {
             config,
             board,
             start_time: game_start,
             current_time: game_start,
             end_time: None,
+            recording,
+            show_probabilities: false,
+            probabilities: None,
+            clicks: ClickCounts::default(),
+            three_bv: None,
+            analysis: None,
+            analysing: false,
+            daily: None,
+            assisted: false,
+            held_buttons: HeldButtons::default(),
+            hovered_cell: None,
+            hovered_gap: None,
+        }
+    }
+    /// Starts a game of `challenge` with its first click already made. The
+    /// game is only ranked if it is the first one played of that day's
+    /// challenge.
+    pub fn build_daily(config: ArcLock<Config>, challenge: DailyChallenge) -> Self {
+        let ranked = !Self::has_played_daily(challenge);
+        if ranked && let Err(err) = Self::save_daily_played(challenge) {
+            eprintln!("Failed to save daily challenge attempt: {err}");
+        }
+        let mut game = Self::build(config, challenge.create_board());
+        game.daily = Some(DailyAttempt { challenge, ranked });
+        game.open_daily_first_click();
+        game
+    }
+    /// Returns whether the ranked game of `challenge` has already been
+    /// started.
+    pub fn has_played_daily(challenge: DailyChallenge) -> bool {
+        let Ok(file) = std::fs::File::open(Self::daily_played_path()) else {
+            return false;
+        };
+        ciborium::from_reader::<DailyChallenge, _>(file)
+            .is_ok_and(|last_played| last_played >= challenge)
+    }
+    fn save_daily_played(challenge: DailyChallenge) -> Result<(), Box<dyn std::error::Error>> {
+        let data_dir = Application::app_dirs().data_dir().to_path_buf();
+        if !data_dir.exists() {
+            std::fs::create_dir_all(&data_dir)?;
+        }
+        let file = std::fs::File::create(Self::daily_played_path())?;
+        ciborium::into_writer(&challenge, file)?;
+        Ok(())
+    }
+    fn daily_played_path() -> PathBuf {
+        Application::app_dirs().data_dir().join("daily_played")
+    }
+    /// Makes the first click of the daily challenge for the player, so that
+    /// everyone starts from the same position.
+    fn open_daily_first_click(&mut self) {
+        let Some(DailyAttempt { challenge, .. }) = self.daily else {
+            return;
+        };
+        let (x, y) = challenge.get_first_click();
+        let previous_state = self.board.get_state();
+        self.board.open_cell(x, y);
+        self.record(RecordedAction::OpenCell(x, y), previous_state);
+        self.apply_assists();
+        self.update_three_bv();
+    }
+    /// Makes the moves that the assists turned on in the settings call for,
+    /// until there are none left, and returns whether any were made. Each one
+    /// is recorded like a move made by the player, so that replays and undo
+    /// work the same.
+    fn apply_assists(&mut self) -> bool {
+        let (auto_flag, auto_open) = {
+            let config = self.config.read().unwrap();
+            (config.auto_flag, config.auto_open)
+        };
+        let mut assisted = false;
+        loop {
+            let mut changed = false;
+            if auto_flag {
+                for (x, y) in self.board.get_obvious_mines() {
+                    let previous_state = self.board.get_state();
+                    if !self.board.toggle_flag(x, y).is_no_op() {
+                        self.record(RecordedAction::ToggleFlag(x, y), previous_state);
+                        changed = true;
+                    }
+                }
+            }
+            if auto_open {
+                for (x, y) in self.board.get_satisfied_numbers() {
+                    let previous_state = self.board.get_state();
+                    if !self.board.chord_cell(x, y).is_no_op() {
+                        self.record(RecordedAction::ChordCell(x, y), previous_state);
+                        changed = true;
+                    }
+                }
+            }
+            assisted |= changed;
+            if !changed || !matches!(self.board.get_state(), BoardState::InProgress) {
+                break;
+            }
+        }
+        self.assisted |= assisted;
+        assisted
+    }
+    /// Returns whether left-clicking `(x, y)` chords on it instead of opening
+    /// it, which only happens on opened cells with the left-click chord style.
+    fn is_left_click_chord(&self, x: u16, y: u16) -> bool {
+        self.config.read().unwrap().chord_style == ChordStyle::LeftClick
+            && self.board.get_cell(x, y).is_some_and(Cell::is_open)
+    }
+    /// Returns the cell that a press acts on, which is the one under the
+    /// pointer, or the first cell of the row whose gap is under it.
+    fn target_cell(&self) -> Option<(u16, u16)> {
+        self.hovered_cell.or(self.hovered_gap.map(|y| (0, y)))
+    }
+    /// Returns the cells that are drawn pushed in whilst a button is held: the
+    /// cell under the pointer, along with its neighbours when chording.
+    fn pressed_cells(&self) -> Vec<(u16, u16)> {
+        let Some((x, y)) = self.target_cell() else {
+            return Vec::new();
+        };
+        let held_buttons = self.held_buttons;
+        if held_buttons.chording || (held_buttons.left && self.is_left_click_chord(x, y)) {
+            std::iter::once((x, y))
+                .chain(self.board.get_surrounding_coordinates(x, y))
+                .collect()
+        } else if held_buttons.left {
+            vec![(x, y)]
+        } else {
+            Vec::new()
+        }
+    }
+    /// Returns whether a win would be ranked on the leaderboard.
+    fn is_ranked(&self) -> bool {
+        !self.board.has_used_undo()
+            && self.board.get_lives() == 1
+            && self.daily.is_none_or(|daily| daily.ranked)
+    }
+    /// Continues the game that was saved when the app last closed. The save is
+    /// removed once it has been loaded, so a game can only be continued once.
+    pub fn restore(config: ArcLock<Config>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = Self::saved_game_path();
+        let file = std::fs::File::open(&path)?;
+        let saved_game: SavedGame = ciborium::from_reader(file)?;
+        std::fs::remove_file(path)?;
+        if saved_game.version != SAVED_GAME_VERSION {
+            return Err(format!("unsupported saved game version {}", saved_game.version).into());
+        }
+        let mut game = Self::build(config, saved_game.board);
+        game.start_time = game
+            .current_time
+            .checked_sub(saved_game.elapsed_time)
+            .unwrap_or(game.current_time);
+        game.recording = saved_game.recording;
+        game.clicks = saved_game.clicks;
+        game.daily = saved_game.daily;
+        game.assisted = saved_game.assisted;
+        game.update_probabilities();
+        game.update_three_bv();
+        Ok(game)
+    }
+    pub fn has_saved_game() -> bool {
+        Self::saved_game_path().exists()
+    }
+    fn save_game(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let data_dir = Application::app_dirs().data_dir().to_path_buf();
+        if !data_dir.exists() {
+            std::fs::create_dir_all(&data_dir)?;
+        }
+        let saved_game = SavedGame {
+            version: SAVED_GAME_VERSION,
+            board: self.board.clone(),
+            elapsed_time: self.start_time.elapsed(),
+            recording: self.recording.clone(),
+            clicks: self.clicks,
+            daily: self.daily,
+            assisted: self.assisted,
+        };
+        let file = std::fs::File::create(Self::saved_game_path())?;
+        ciborium::into_writer(&saved_game, file)?;
+        Ok(())
+    }
+    fn saved_game_path() -> PathBuf {
+        Application::app_dirs().data_dir().join("saved_game")
+    }
+    /// Adds an action to the replay, which is saved once the action has ended
+    /// the game.
+    fn record(&mut self, action: RecordedAction, previous_state: BoardState) {
+        self.recording.record(self.start_time.elapsed(), action);
+        let game_ended = matches!(previous_state, BoardState::InProgress)
+            && !matches!(self.board.get_state(), BoardState::InProgress);
+        if game_ended && let Err(err) = self.recording.save(&self.board) {
+            eprintln!("Failed to save replay: {err}");
+        }
+    }
+    /// Returns the statistics for the game so far, or `None` if the mines have
+    /// not been placed yet.
+    fn statistics(&self) -> Option<Statistics> {
+        Some(Statistics {
+            three_bv: self.three_bv?,
+            clicks: self.clicks,
+        })
+    }
+    fn update_probabilities(&mut self) {
+        // Once the game is over the probabilities from before the final move are
+        // kept, so that a loss can still be looked over.
+        if self.show_probabilities && matches!(self.board.get_state(), BoardState::InProgress) {
+            self.probabilities = solver::get_mine_probabilities(&self.board);
         }
     }
+    /// Works out the board's 3BV once its mines have been placed.
+    fn update_three_bv(&mut self) {
+        if self.three_bv.is_none() {
+            self.three_bv = self.board.get_3bv();
+        }
+    }
+    /// Starts analysing the board in the background once its mines have been
+    /// placed, which only happens once per game as the layout does not change
+    /// after that.
+    fn analyse_board(&mut self) -> Option<Task<SuperMessage>> {
+        if self.analysis.is_some() || self.analysing || !self.board.has_mines() {
+            return None;
+        }
+        self.analysing = true;
+        let board = self.board.clone();
+        let seed = board.get_seed();
+        Some(Task::perform(async move { board.analyse() }, move |analysis| {
+            SuperMessage::Game(Message::BoardAnalysed(seed, analysis))
+        }))
+    }
 }
 
 impl Screen for Game {
@@ -50,7 +421,15 @@ impl Screen for Game {
         let config = self.config.clone();
         match message {
             Message::OpenCell(x, y) => {
-                self.board.open_cell(x, y);
+                self.clicks.left += 1;
+                let previous_state = self.board.get_state();
+                if self.board.open_cell(x, y).is_no_op() {
+                    return None;
+                }
+                self.record(RecordedAction::OpenCell(x, y), previous_state);
+                self.apply_assists();
+                self.update_probabilities();
+                self.update_three_bv();
                 if matches!(self.board.get_state(), BoardState::Won) && self.end_time.is_none() {
                     self.end_time = Some(SystemTime::now());
                 }
@@ -61,7 +440,29 @@ impl Screen for Game {
                 )
             }
             Message::ToggleFlag(x, y) => {
-                self.board.toggle_flag(x, y);
+                self.clicks.right += 1;
+                let previous_state = self.board.get_state();
+                // With question marks turned on, right-clicking cycles through the marks
+                // instead of only toggling the flag
+                let (outcome, action) = if config.read().unwrap().question_marks {
+                    (self.board.cycle_mark(x, y), RecordedAction::CycleMark(x, y))
+                } else {
+                    (self.board.toggle_flag(x, y), RecordedAction::ToggleFlag(x, y))
+                };
+                if outcome.is_no_op() {
+                    return None;
+                }
+                self.record(action, previous_state);
+                // Assists only follow on from placing a flag, so that a flag the player
+                // took off is not put straight back
+                if self.board.get_cell(x, y).is_some_and(Cell::is_flagged) && self.apply_assists()
+                {
+                    self.update_probabilities();
+                    if matches!(self.board.get_state(), BoardState::Won) && self.end_time.is_none()
+                    {
+                        self.end_time = Some(SystemTime::now());
+                    }
+                }
                 Some(
                     Task::done(Instant::now())
                         .map(Message::TimeUpdate)
@@ -69,7 +470,14 @@ impl Screen for Game {
                 )
             }
             Message::ChordCell(x, y) => {
-                self.board.chord_cell(x, y);
+                self.clicks.chord += 1;
+                let previous_state = self.board.get_state();
+                if self.board.chord_cell(x, y).is_no_op() {
+                    return None;
+                }
+                self.record(RecordedAction::ChordCell(x, y), previous_state);
+                self.apply_assists();
+                self.update_probabilities();
                 if matches!(self.board.get_state(), BoardState::Won) && self.end_time.is_none() {
                     self.end_time = Some(SystemTime::now());
                 }
@@ -79,31 +487,153 @@ impl Screen for Game {
                         .map(SuperMessage::Game),
                 )
             }
+            Message::CellEntered(x, y) => {
+                self.hovered_cell = Some((x, y));
+                None
+            }
+            Message::CellExited(x, y) => {
+                // The next cell may already have been entered
+                if self.hovered_cell == Some((x, y)) {
+                    self.hovered_cell = None;
+                }
+                None
+            }
+            // The gap is tracked apart from the cells, as it stands for the same cell as
+            // the one beside it
+            Message::GapEntered(y) => {
+                self.hovered_gap = Some(y);
+                None
+            }
+            Message::GapExited(y) => {
+                if self.hovered_gap == Some(y) {
+                    self.hovered_gap = None;
+                }
+                None
+            }
+            Message::ButtonPressed(button) => {
+                // Presses that start off the board are left to the rest of the screen
+                let (x, y) = self.target_cell()?;
+                let chord_style = config.read().unwrap().chord_style;
+                match button {
+                    mouse::Button::Left => {
+                        self.held_buttons.left = true;
+                        self.held_buttons.chording |=
+                            chord_style == ChordStyle::BothButtons && self.held_buttons.right;
+                    }
+                    mouse::Button::Middle => {
+                        self.held_buttons.middle = true;
+                        self.held_buttons.chording = true;
+                    }
+                    mouse::Button::Right
+                        if chord_style == ChordStyle::BothButtons && self.held_buttons.left =>
+                    {
+                        self.held_buttons.right = true;
+                        self.held_buttons.chording = true;
+                    }
+                    // Flags go down as soon as the button is pressed, as there is nothing to
+                    // preview
+                    mouse::Button::Right => {
+                        self.held_buttons.right = true;
+                        return Some(Task::done(SuperMessage::Game(Message::ToggleFlag(x, y))));
+                    }
+                    _ => {}
+                }
+                None
+            }
+            Message::ButtonReleased(button) => {
+                let was_held = match button {
+                    mouse::Button::Left => std::mem::take(&mut self.held_buttons.left),
+                    mouse::Button::Right => std::mem::take(&mut self.held_buttons.right),
+                    mouse::Button::Middle => std::mem::take(&mut self.held_buttons.middle),
+                    _ => false,
+                };
+                let held_buttons = self.held_buttons;
+                // Once a chord or an opening is made, the rest of the buttons are let go
+                // without doing anything else
+                if was_held && (held_buttons.chording || button == mouse::Button::Left) {
+                    self.held_buttons = HeldButtons::default();
+                }
+                if !held_buttons.left && !held_buttons.right && !held_buttons.middle {
+                    self.held_buttons = HeldButtons::default();
+                }
+                // Letting go off the board cancels the press
+                let (x, y) = self.target_cell()?;
+                if !was_held {
+                    return None;
+                }
+                let is_left = button == mouse::Button::Left;
+                let message = if held_buttons.chording || (is_left && self.is_left_click_chord(x, y))
+                {
+                    Message::ChordCell(x, y)
+                } else if is_left {
+                    Message::OpenCell(x, y)
+                } else {
+                    return None;
+                };
+                Some(Task::done(SuperMessage::Game(message)))
+            }
             Message::ResetGame => {
-                let (width, height, mines) = (
-                    self.board.get_width(),
-                    self.board.get_height(),
-                    self.board.get_mine_count(),
-                );
-                let new_board = unsafe {
-                    // SAFETY: Rows, columns, and mines are guaranteed to be non-zero since they
-                    // were used to create the current board.
-                    Board::create_custom(
-                        NonZeroU8::new_unchecked(width),
-                        NonZeroU8::new_unchecked(height),
-                        NonZeroU16::new_unchecked(mines),
-                    )
-                    .unwrap()
+                // The daily challenge always restarts on the same board, but can only be
+                // ranked once
+                let new_board = match &mut self.daily {
+                    Some(daily) => {
+                        daily.ranked = false;
+                        daily.challenge.create_board()
+                    }
+                    None => self.board.new_game(),
                 };
                 let new_start = Instant::now();
                 self.start_time = new_start;
                 self.current_time = new_start;
                 self.end_time = None;
+                self.clicks = ClickCounts::default();
+                self.three_bv = None;
+                self.analysis = None;
+                self.analysing = false;
+                self.assisted = false;
+                self.held_buttons = HeldButtons::default();
+                self.recording = Recording::create(&new_board);
                 self.board = new_board;
+                self.open_daily_first_click();
+                self.update_probabilities();
+                None
+            }
+            Message::Undo => {
+                let previous_state = self.board.get_state();
+                if self.board.undo() {
+                    self.record(RecordedAction::Undo, previous_state);
+                    if matches!(self.board.get_state(), BoardState::InProgress) {
+                        self.end_time = None;
+                    }
+                    self.update_probabilities();
+                }
+                None
+            }
+            Message::Redo => {
+                let previous_state = self.board.get_state();
+                if self.board.redo() {
+                    self.record(RecordedAction::Redo, previous_state);
+                    if matches!(self.board.get_state(), BoardState::Won) && self.end_time.is_none()
+                    {
+                        self.end_time = Some(SystemTime::now());
+                    }
+                    self.update_probabilities();
+                }
                 None
             }
             Message::TimeUpdate(time) => {
                 self.current_time = time;
+                // Every move and every second of play is followed by a time update, so
+                // the analysis starts soon after the mines are placed however that
+                // happened
+                self.analyse_board()
+            }
+            Message::BoardAnalysed(seed, analysis) => {
+                // The game may have been reset whilst the analysis was running
+                if seed == self.board.get_seed() {
+                    self.analysis = analysis;
+                    self.analysing = false;
+                }
                 None
             }
             Message::Back => Some(
@@ -116,19 +646,32 @@ impl Screen for Game {
             Message::SaveTime => {
                 let duration = self.current_time.duration_since(self.start_time);
                 let end_time = chrono::Utc::now();
-                let (width, height, mines) = (
-                    self.board.get_width(),
-                    self.board.get_height(),
-                    self.board.get_mine_count(),
-                );
+                let board = self.board.clone();
+                let statistics = self.statistics();
+                let daily_date = self.daily.map(|daily| daily.challenge.get_date());
+                let assisted = self.assisted;
+                let analysis = self.analysis.clone();
                 Some(
                     Task::perform(
                         async move {
+                            // The analysis in the background may not have finished yet, in
+                            // which case the board is analysed again here so that every time
+                            // is graded
+                            let difficulty = analysis
+                                .or_else(|| board.analyse())
+                                .expect("a won board has its mines placed")
+                                .get_difficulty();
                             Leaderboard::from_new_time(
                                 config,
-                                chrono::TimeDelta::from_std(duration).unwrap(),
-                                end_time,
-                                (width, height, mines),
+                                FinishedGame {
+                                    time: chrono::TimeDelta::from_std(duration).unwrap(),
+                                    completion_date: end_time,
+                                    board,
+                                    statistics,
+                                    daily_date,
+                                    assisted,
+                                    difficulty,
+                                },
                             )
                         },
                         move |item| Arc::new(Box::new(item) as Box<dyn Screen>),
@@ -137,6 +680,25 @@ impl Screen for Game {
                     .map(SuperMessage::App),
                 )
             }
+            Message::WatchReplay => {
+                if matches!(self.board.get_state(), BoardState::InProgress) {
+                    return None;
+                }
+                let recording = self.recording.clone();
+                Some(
+                    Task::perform(async { Replay::build(config, recording) }, move |item| {
+                        Arc::new(Box::new(item) as Box<dyn Screen>)
+                    })
+                    .map(AppMessage::ChangeScreen)
+                    .map(SuperMessage::App),
+                )
+            }
+            Message::ProbabilitiesToggled(show_probabilities) => {
+                self.show_probabilities = show_probabilities;
+                self.probabilities = None;
+                self.update_probabilities();
+                None
+            }
         }
     }
     fn view(&self) -> Element<'_, SuperMessage> {
@@ -146,22 +708,104 @@ impl Screen for Game {
         let top_bar = GuiWidget::container(self.top_bar())
             .style(GuiWidget::container::bordered_box)
             .padding(10);
-        let mut game_content = GuiWidget::column![top_bar, board]
+        let menu_theme = &self.config.read().unwrap().menu_theme;
+        let probabilities_checkbox = GuiWidget::checkbox(self.show_probabilities)
+            .label("Show mine probabilities")
+            .on_toggle(|show_probabilities| {
+                SuperMessage::Game(Message::ProbabilitiesToggled(show_probabilities))
+            })
+            .font(menu_theme.default_font())
+            .text_size(menu_theme.default_text_size());
+        let undo_button = menu_theme
+            .button(menu_theme.text("Undo"), crate::MenuButtonStyle::Secondary)
+            .on_press_maybe(
+                self.board
+                    .can_undo()
+                    .then_some(SuperMessage::Game(Message::Undo)),
+            );
+        let redo_button = menu_theme
+            .button(menu_theme.text("Redo"), crate::MenuButtonStyle::Secondary)
+            .on_press_maybe(
+                self.board
+                    .can_redo()
+                    .then_some(SuperMessage::Game(Message::Redo)),
+            );
+        let difficulty_badge = self.analysis.as_ref().map(|analysis| {
+            GuiWidget::container(
+                menu_theme.text(format!("{} board", analysis.get_difficulty())),
+            )
+            .style(GuiWidget::container::bordered_box)
+            .padding(5)
+        });
+        let history_buttons = GuiWidget::row![undo_button, redo_button, difficulty_badge]
             .spacing(10)
-            .align_x(iced::Center);
+            .align_y(iced::Alignment::Center);
+        let daily_text = self.daily.map(|daily| {
+            menu_theme.text(format!(
+                "Daily challenge for {}{}",
+                daily.challenge.get_date().format("%-d %B %Y"),
+                if daily.ranked { "" } else { " (unranked)" }
+            ))
+        });
+        let mut game_content = GuiWidget::column![
+            daily_text,
+            top_bar,
+            board,
+            history_buttons,
+            probabilities_checkbox
+        ]
+        .spacing(10)
+        .align_x(iced::Center);
         if let Some(end_content) = self.end_of_screen() {
             game_content = game_content.push(end_content);
         }
         GuiWidget::center(game_content).into()
     }
     fn subscription(&self) -> Option<Subscription<SuperMessage>> {
+        // Ctrl+Z undoes, whilst both Ctrl+Y and Ctrl+Shift+Z redo
+        let history_subscription = iced::keyboard::on_key_press(|key, modifiers| {
+            let iced::keyboard::Key::Character(character) = key else {
+                return None;
+            };
+            if !modifiers.command() {
+                return None;
+            }
+            match (character.as_str(), modifiers.shift()) {
+                ("z", false) => Some(SuperMessage::Game(Message::Undo)),
+                ("z", true) | ("y", false) => Some(SuperMessage::Game(Message::Redo)),
+                _ => None,
+            }
+        });
         match self.board.get_state() {
-            BoardState::InProgress => Some(
+            BoardState::InProgress => Some(Subscription::batch([
+                history_subscription,
+                iced::event::listen_with(|event, status, _| match event {
+                    // Presses that a widget has already handled, such as a click on a
+                    // button, are not meant for the board. Releases always get through,
+                    // so that no button is left held.
+                    iced::Event::Mouse(mouse::Event::ButtonPressed(button))
+                        if status == iced::event::Status::Ignored =>
+                    {
+                        Some(SuperMessage::Game(Message::ButtonPressed(button)))
+                    }
+                    iced::Event::Mouse(mouse::Event::ButtonReleased(button)) => {
+                        Some(SuperMessage::Game(Message::ButtonReleased(button)))
+                    }
+                    _ => None,
+                }),
                 iced::time::every(std::time::Duration::from_secs(1))
                     .map(Message::TimeUpdate)
                     .map(SuperMessage::Game),
-            ),
-            _ => None,
+            ])),
+            _ => Some(history_subscription),
+        }
+    }
+    fn on_close(&mut self) {
+        // Only games that have been started and are not yet over are worth continuing
+        if self.board.has_mines() && matches!(self.board.get_state(), BoardState::InProgress) {
+            self.save_game().unwrap_or_else(|err| {
+                eprintln!("Failed to save game: {err}");
+            });
         }
     }
 }
@@ -170,8 +814,8 @@ macro_rules! impl_game_image {
     ($([$static_name:ident, $function_name:ident]),*) => {
         impl Game {
             $(
-                fn $function_name(&self) -> Element<'_, SuperMessage> {
-                    match self.config.read().unwrap().game_theme {
+                fn $function_name(game_theme: &GameTheme) -> Element<'static, SuperMessage> {
+                    match game_theme {
                         GameTheme::SimpleLight => GuiSvg::Svg::new(GuiSvg::Handle::from_memory(
                             crate::gui::assets::simple_light::$static_name.as_slice(),
                         )).into(),
@@ -194,6 +838,7 @@ impl_game_image!(
     [OPENED_CELL, opened_cell],
     [MINE, mine],
     [FLAG, flag],
+    [QUESTION_MARK, question_mark],
     [INCORRECT_FLAG, incorrect_flag],
     [EXPLODED_MINE, exploded_mine]
 );
@@ -210,6 +855,14 @@ impl Game {
     pub fn top_bar(&self) -> Element<'_, SuperMessage> {
         let remaining_mine_count = self.board.get_remaining_mine_count();
         let mine_count_text = GuiWidget::text(format!("{remaining_mine_count:03}"));
+        // Lives are only worth showing when the game has more than one
+        let lives_text = (self.board.get_lives() > 1).then(|| {
+            GuiWidget::text!(
+                "Lives: {}/{}",
+                self.board.get_remaining_lives(),
+                self.board.get_lives()
+            )
+        });
 
         let reset_button = self.reset_button();
 
@@ -217,7 +870,8 @@ impl Game {
         let time_text = GuiWidget::text!("{elapsed_time:03}");
 
         let content = GuiWidget::row![
-            GuiWidget::container(mine_count_text).width(iced::Fill),
+            GuiWidget::container(GuiWidget::row![mine_count_text, lives_text].spacing(10))
+                .width(iced::Fill),
             GuiWidget::center_x(reset_button),
             GuiWidget::right(time_text)
         ];
@@ -229,12 +883,24 @@ impl Game {
         let menu_theme = &self.config.read().unwrap().menu_theme;
 
         let text = menu_theme.text(match self.board.get_state() {
+            BoardState::Won if self.board.has_used_undo() => {
+                "You found all the mines. You win! Games that used undo are not ranked."
+            }
+            BoardState::Won if self.board.get_lives() > 1 => {
+                "You found all the mines. You win! Games with extra lives are not ranked."
+            }
+            BoardState::Won if !self.is_ranked() => {
+                "You found all the mines. You win! Only the first daily challenge of the day is ranked."
+            }
             BoardState::Won => "You found all the mines. You win!",
+            BoardState::Lost if self.board.get_lives() > 1 => "You ran out of lives! You lose!",
             BoardState::Lost => "You hit a mine! You lose!",
             BoardState::InProgress => "",
         });
 
-        let possible_save_time = matches!(self.board.get_state(), BoardState::Won).then(|| {
+        let possible_save_time = (matches!(self.board.get_state(), BoardState::Won)
+            && self.is_ranked())
+        .then(|| {
             menu_theme
                 .button(
                     menu_theme.text("Save Time"),
@@ -248,57 +914,154 @@ impl Game {
                 crate::MenuButtonStyle::Secondary,
             )
             .on_press(SuperMessage::Game(Message::Back));
-        let buttons = if let Some(save_time_button) = possible_save_time {
-            GuiWidget::row![save_time_button, return_button]
-        } else {
-            GuiWidget::row![return_button]
-        }
-        .spacing(10)
-        .align_y(iced::Center);
+        let game_over = !matches!(self.board.get_state(), BoardState::InProgress);
+        // The recording only has the mines once the game is over
+        let replay_button = game_over.then(|| {
+            menu_theme
+                .button(
+                    menu_theme.text("Watch replay"),
+                    crate::MenuButtonStyle::Secondary,
+                )
+                .on_press(SuperMessage::Game(Message::WatchReplay))
+        });
+        let buttons = GuiWidget::row![possible_save_time, replay_button, return_button]
+            .spacing(10)
+            .align_y(iced::Center);
+
+        // Shown only once the game is over, as the 3BV would give away how hard the
+        // rest of the board is
+        let statistics_text = self.statistics().filter(|_| game_over).map(|statistics| {
+            let time = self.current_time.duration_since(self.start_time);
+            menu_theme.text(format!(
+                "3BV: {} ({:.2}/s) | Clicks: {} left, {} right, {} chord | IOE: {:.2}{}",
+                statistics.three_bv,
+                statistics.get_3bv_per_second(time),
+                statistics.clicks.left,
+                statistics.clicks.right,
+                statistics.clicks.chord,
+                statistics.get_efficiency(),
+                if statistics.is_no_flag() { " | NF" } else { "" }
+            ))
+        });
 
-        let content = GuiWidget::column![text, buttons]
+        let content = GuiWidget::column![text, statistics_text, buttons]
+            .spacing(5)
             .align_x(iced::Center)
             .into();
         Some(content)
     }
     pub fn board(&self) -> impl Into<Element<'_, SuperMessage>> {
-        let mut board_content = GuiWidget::Grid::with_capacity(
-            self.board.get_width() as usize * self.board.get_height() as usize,
+        let pressed_cells = self.pressed_cells();
+        Self::board_layout(
+            &self.board,
+            |x, y| self.cell(x, y, pressed_cells.contains(&(x, y))),
+            // The gap counts as part of the first cell of its row, so that pressing on
+            // it is not lost
+            |y, gap| {
+                GuiWidget::mouse_area(gap)
+                    .on_enter(SuperMessage::Game(Message::GapEntered(y)))
+                    .on_exit(SuperMessage::Game(Message::GapExited(y)))
+                    .into()
+            },
         )
-        .columns(self.board.get_width() as usize)
-        .width(self.board.get_width() as f32 * 16.0);
-        for y in 0..self.board.get_height() {
-            for x in 0..self.board.get_width() {
-                board_content = board_content.push(self.cell(x, y));
+    }
+    /// Lays out the cells of `board` in a grid, or on a hexagonal board in rows
+    /// like a brick wall, with every odd row shifted half a cell to the right.
+    /// The gap that shifts row `y` is passed through `gap` before it is laid
+    /// out.
+    pub fn board_layout<'a>(
+        board: &Board,
+        mut cell: impl FnMut(u16, u16) -> Element<'a, SuperMessage>,
+        mut gap: impl FnMut(u16, Element<'a, SuperMessage>) -> Element<'a, SuperMessage>,
+    ) -> Element<'a, SuperMessage> {
+        if let Topology::Hexagonal = board.get_topology() {
+            let rows = (0..board.get_height()).map(|y| {
+                let mut row = GuiWidget::Row::with_capacity(board.get_width() as usize + 1);
+                if !y.is_multiple_of(2) {
+                    row = row.push(gap(y, GuiWidget::space().width(8).into()));
+                }
+                for x in 0..board.get_width() {
+                    row = row.push(cell(x, y));
+                }
+                row.into()
+            });
+            return GuiWidget::Column::with_children(rows.collect::<Vec<_>>())
+                .width(board.get_width() as f32 * 16.0 + 8.0)
+                .into();
+        }
+        let mut board_content =
+            GuiWidget::Grid::with_capacity(board.get_width() as usize * board.get_height() as usize)
+                .columns(board.get_width() as usize)
+                .width(board.get_width() as f32 * 16.0);
+        for y in 0..board.get_height() {
+            for x in 0..board.get_width() {
+                board_content = board_content.push(cell(x, y));
             }
         }
-        board_content
+        board_content.into()
     }
-    pub fn cell(&self, x: u8, y: u8) -> Element<'_, SuperMessage> {
+    pub fn cell(&self, x: u16, y: u16, is_pressed: bool) -> Element<'_, SuperMessage> {
         let cell = self.board.get_cell(x, y).unwrap();
-        let content = self.cell_content(cell);
-        let is_playing = matches!(self.board.get_state(), BoardState::InProgress);
-        if is_playing {
-            GuiWidget::mouse_area(content)
-                .on_press(SuperMessage::Game(Message::OpenCell(x, y)))
-                .on_right_press(SuperMessage::Game(Message::ToggleFlag(x, y)))
-                .on_middle_press(SuperMessage::Game(Message::ChordCell(x, y)))
-                .into()
+        let game_theme = &self.config.read().unwrap().game_theme;
+        let mut content = if is_pressed && !cell.is_open() && !cell.is_flagged() {
+            // A pressed cell looks like an empty opened one, as in the classic game
+            Self::opened_cell(game_theme)
         } else {
-            content
+            Self::cell_content(game_theme, self.board.get_state(), cell)
+        };
+        if let Some(probability) = self
+            .probabilities
+            .as_ref()
+            .filter(|_| self.show_probabilities && !cell.is_flagged())
+            .and_then(|probabilities| probabilities.get(x, y))
+        {
+            content = self.probability_overlay(content, probability);
         }
+        let is_playing = matches!(self.board.get_state(), BoardState::InProgress);
+        if !is_playing {
+            return content;
+        }
+        // The buttons themselves are picked up by the screen's subscription, so that
+        // letting go of a button off the board is seen too
+        GuiWidget::mouse_area(content)
+            .on_enter(SuperMessage::Game(Message::CellEntered(x, y)))
+            .on_exit(SuperMessage::Game(Message::CellExited(x, y)))
+            .into()
     }
-    pub fn cell_content(&self, cell: &Cell) -> Element<'_, SuperMessage> {
+    fn probability_overlay<'a>(
+        &self,
+        content: Element<'a, SuperMessage>,
+        probability: f64,
+    ) -> Element<'a, SuperMessage> {
+        let shade = GuiWidget::container(GuiWidget::Space::new())
+            .width(16)
+            .height(16)
+            .style(move |_| GuiWidget::container::Style {
+                background: Some(iced::Background::Color(iced::Color {
+                    a: probability as f32 * 0.75,
+                    ..iced::Color::from_rgb8(255, 0, 0)
+                })),
+                ..Default::default()
+            });
+        GuiWidget::stack![content, shade].into()
+    }
+    /// Draws a cell as it appears on a board in the given state, without any
+    /// way of interacting with it.
+    pub fn cell_content(
+        game_theme: &GameTheme,
+        board_state: BoardState,
+        cell: &Cell,
+    ) -> Element<'static, SuperMessage> {
         let content = if cell.is_open() {
             if cell.is_mine() {
-                self.exploded_mine()
+                Self::exploded_mine(game_theme)
             } else {
                 let adjacent_mines = cell.adjacent_mines().unwrap();
                 if let cell::AdjacentMines::Zero = adjacent_mines {
-                    self.opened_cell()
+                    Self::opened_cell(game_theme)
                 } else {
                     let mut stack = GuiWidget::Stack::with_capacity(2);
-                    stack = stack.push(self.opened_cell());
+                    stack = stack.push(Self::opened_cell(game_theme));
                     let color = match adjacent_mines {
                         cell::AdjacentMines::One => iced::color!(0, 0, 255),
                         cell::AdjacentMines::Two => iced::color!(0, 127, 0),
@@ -314,7 +1077,7 @@ impl Game {
                     let text = GuiWidget::center(
                         GuiWidget::text!("{adjacent_mines}")
                             .size(14)
-                            .font(self.config.read().unwrap().game_theme.mine_font())
+                            .font(game_theme.mine_font())
                             .color(color),
                     )
                     .width(16)
@@ -324,30 +1087,36 @@ impl Game {
                 }
             }
         } else {
-            match self.board.get_state() {
+            match board_state {
                 BoardState::InProgress => {
                     if cell.is_flagged() {
-                        self.flag()
+                        Self::flag(game_theme)
+                    } else if cell.is_question_marked() {
+                        Self::question_mark(game_theme)
                     } else {
-                        self.unopened_cell()
+                        Self::unopened_cell(game_theme)
                     }
                 }
                 BoardState::Won => {
                     if cell.is_mine() || cell.is_flagged() {
-                        self.flag()
+                        Self::flag(game_theme)
+                    } else if cell.is_question_marked() {
+                        Self::question_mark(game_theme)
                     } else {
-                        self.unopened_cell()
+                        Self::unopened_cell(game_theme)
                     }
                 }
                 BoardState::Lost => {
                     if cell.is_mine() && !cell.is_flagged() {
-                        self.mine()
+                        Self::mine(game_theme)
                     } else if !cell.is_mine() && cell.is_flagged() {
-                        self.incorrect_flag()
+                        Self::incorrect_flag(game_theme)
                     } else if cell.is_flagged() {
-                        self.flag()
+                        Self::flag(game_theme)
+                    } else if cell.is_question_marked() {
+                        Self::question_mark(game_theme)
                     } else {
-                        self.unopened_cell()
+                        Self::unopened_cell(game_theme)
                     }
                 }
             }
@@ -355,3 +1124,25 @@ impl Game {
         GuiWidget::center(content).width(16).height(16).into()
     }
 }
+
+#[cfg(test)]
+mod testing {
+    use std::sync::RwLock;
+
+    use super::*;
+    #[test]
+    fn test_game_assist_win() {
+        let config = Arc::new(RwLock::new(Config {
+            auto_open: true,
+            ..Config::default()
+        }));
+        let board = Board::create_from_text("1*\n1.").unwrap();
+        let mut game = Game::build(config, board);
+        // Flagging the mine satisfies the numbers, so the auto-open wins the game
+        game.update(SuperMessage::Game(Message::ToggleFlag(1, 0)));
+        assert!(matches!(game.board.get_state(), BoardState::Won));
+        // The time is only ranked against other assisted games
+        assert!(game.assisted);
+        assert!(game.end_time.is_some());
+    }
+}
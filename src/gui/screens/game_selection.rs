@@ -1,9 +1,9 @@
-﻿use std::sync::Arc;
+﻿use std::{num::NonZeroU32, sync::Arc};
 
 use iced::{Task, widget as GuiWidget};
 
 use super::{AppMessage, CustomSetup, Game, MainMenu, Message as SuperMessage};
-use crate::{ArcLock, Board, Config, Screen};
+use crate::{ArcLock, Board, Config, GenerationMode, Screen, core::daily::DailyChallenge};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -11,17 +11,42 @@ pub enum Message {
     IntermediateSelected,
     ExpertSelected,
     CustomSelected,
+    DailySelected,
+    NoGuessToggled(bool),
+    LivesToggled(bool),
     Back,
 }
 
 #[derive(Debug)]
 pub struct GameSelection {
     config: ArcLock<Config>,
+    no_guess: bool,
+    lives_mode: bool,
+    played_daily: bool,
 }
 
 impl GameSelection {
     pub fn build(config: ArcLock<Config>) -> Self {
-        Self { config }
+        Self {
+            config,
+            no_guess: false,
+            lives_mode: false,
+            played_daily: Game::has_played_daily(DailyChallenge::create_today()),
+        }
+    }
+    fn generation_mode(&self) -> GenerationMode {
+        if self.no_guess {
+            GenerationMode::NoGuess
+        } else {
+            GenerationMode::Random
+        }
+    }
+    fn lives(&self) -> NonZeroU32 {
+        if self.lives_mode {
+            Game::LIVES_MODE_LIVES
+        } else {
+            NonZeroU32::MIN
+        }
     }
 }
 
@@ -31,10 +56,21 @@ impl Screen for GameSelection {
             return None;
         };
         let config = self.config.clone();
+        let generation_mode = self.generation_mode();
+        let first_click_policy = self.config.read().unwrap().first_click_policy;
+        let lives = self.lives();
         match message {
             Message::BeginnerSelected => Some(
                 Task::perform(
-                    async { Game::build(config, Board::create_beginner()) },
+                    async move {
+                        Game::build(
+                            config,
+                            Board::create_beginner()
+                                .with_generation_mode(generation_mode)
+                                .with_first_click_policy(first_click_policy)
+                                .with_lives(lives),
+                        )
+                    },
                     move |item| Arc::new(Box::new(item) as Box<dyn Screen>),
                 )
                 .map(AppMessage::ChangeScreen)
@@ -42,7 +78,15 @@ impl Screen for GameSelection {
             ),
             Message::IntermediateSelected => Some(
                 Task::perform(
-                    async { Game::build(config, Board::create_intermediate()) },
+                    async move {
+                        Game::build(
+                            config,
+                            Board::create_intermediate()
+                                .with_generation_mode(generation_mode)
+                                .with_first_click_policy(first_click_policy)
+                                .with_lives(lives),
+                        )
+                    },
                     move |item| Arc::new(Box::new(item) as Box<dyn Screen>),
                 )
                 .map(AppMessage::ChangeScreen)
@@ -50,7 +94,15 @@ impl Screen for GameSelection {
             ),
             Message::ExpertSelected => Some(
                 Task::perform(
-                    async { Game::build(config, Board::create_expert()) },
+                    async move {
+                        Game::build(
+                            config,
+                            Board::create_expert()
+                                .with_generation_mode(generation_mode)
+                                .with_first_click_policy(first_click_policy)
+                                .with_lives(lives),
+                        )
+                    },
                     move |item| Arc::new(Box::new(item) as Box<dyn Screen>),
                 )
                 .map(AppMessage::ChangeScreen)
@@ -63,6 +115,22 @@ impl Screen for GameSelection {
                 .map(AppMessage::ChangeScreen)
                 .map(SuperMessage::App),
             ),
+            Message::DailySelected => Some(
+                Task::perform(
+                    async { Game::build_daily(config, DailyChallenge::create_today()) },
+                    move |item| Arc::new(Box::new(item) as Box<dyn Screen>),
+                )
+                .map(AppMessage::ChangeScreen)
+                .map(SuperMessage::App),
+            ),
+            Message::NoGuessToggled(no_guess) => {
+                self.no_guess = no_guess;
+                None
+            }
+            Message::LivesToggled(lives_mode) => {
+                self.lives_mode = lives_mode;
+                None
+            }
             Message::Back => Some(
                 Task::perform(async { MainMenu::build(config) }, move |item| {
                     Arc::new(Box::new(item) as Box<dyn Screen>)
@@ -96,12 +164,36 @@ impl Screen for GameSelection {
         let custom_button = menu_theme
             .button(menu_theme.text("Custom"), crate::MenuButtonStyle::Primary)
             .on_press(SuperMessage::GameSelection(Message::CustomSelected));
+        // The daily challenge can be replayed, but only the first game of the day is
+        // ranked
+        let daily_label = if self.played_daily {
+            "Daily Challenge (played today)"
+        } else {
+            "Daily Challenge"
+        };
+        let daily_button = menu_theme
+            .button(menu_theme.text(daily_label), crate::MenuButtonStyle::Primary)
+            .on_press(SuperMessage::GameSelection(Message::DailySelected));
+
+        let no_guess_checkbox = GuiWidget::checkbox(self.no_guess)
+            .label("No guessing")
+            .on_toggle(|no_guess| SuperMessage::GameSelection(Message::NoGuessToggled(no_guess)))
+            .font(menu_theme.default_font())
+            .text_size(menu_theme.default_text_size());
+        let lives_checkbox = GuiWidget::checkbox(self.lives_mode)
+            .label(format!("{} lives", Game::LIVES_MODE_LIVES))
+            .on_toggle(|lives_mode| SuperMessage::GameSelection(Message::LivesToggled(lives_mode)))
+            .font(menu_theme.default_font())
+            .text_size(menu_theme.default_text_size());
 
         let buttons = GuiWidget::column![
             beginner_button,
             intermediate_button,
             expert_button,
-            custom_button
+            custom_button,
+            daily_button,
+            no_guess_checkbox,
+            lives_checkbox
         ]
         .spacing(10)
         .align_x(iced::Center);
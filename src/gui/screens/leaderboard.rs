@@ -1,24 +1,50 @@
 ﻿use std::{collections::BTreeSet, sync::Arc};
 
-use chrono::{DateTime, TimeDelta};
+use chrono::{DateTime, NaiveDate, TimeDelta};
 use iced::{Element, Task, widget as GuiWidget};
 
-use super::{AppMessage, MainMenu, Message as SuperMessage};
-use crate::{Application, ArcLock, Config, Screen};
+use super::{AppMessage, MainMenu, Message as SuperMessage, game::Statistics};
+use crate::{
+    Application, ArcLock, Board, Config, FirstClickPolicy, GenerationMode, Screen, Topology,
+    core::analysis::Difficulty,
+};
 #[derive(Debug)]
 pub struct Leaderboard {
     config: ArcLock<Config>,
     entries: BTreeSet<LeaderboardEntry>,
     new_entry: Option<LeaderboardEntry>,
     current_tab: Tab,
+    show_easy_boards: bool,
+    show_assisted: bool,
+    show_no_guess: bool,
+    /// The date whose times are shown on the daily tab.
+    daily_date: NaiveDate,
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Tab {
     All,
     Beginner,
     Intermediate,
     Expert,
+    Wrapping,
+    Hexagonal,
+    Daily,
+}
+
+/// A won game, with everything about it that decides where its time is
+/// ranked.
+#[derive(Debug)]
+pub struct FinishedGame {
+    pub time: TimeDelta,
+    pub completion_date: DateTime<chrono::Utc>,
+    pub board: Board,
+    pub statistics: Option<Statistics>,
+    /// The date of the daily challenge that was played, if it was one.
+    pub daily_date: Option<NaiveDate>,
+    /// Whether the assists made any moves during the game.
+    pub assisted: bool,
+    pub difficulty: Difficulty,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
@@ -30,11 +56,29 @@ struct LeaderboardEntry {
     #[serde(with = "chrono::serde::ts_seconds", rename = "d")]
     completion_date: DateTime<chrono::Utc>,
     #[serde(rename = "w")]
-    width: u8,
+    width: u16,
     #[serde(rename = "h")]
-    height: u8,
+    height: u16,
     #[serde(rename = "m")]
-    mines: u16,
+    mines: u32,
+    #[serde(rename = "o", default)]
+    topology: Topology,
+    #[serde(rename = "f", default)]
+    first_click_policy: FirstClickPolicy,
+    /// Missing from times set before statistics were kept.
+    #[serde(rename = "s", default)]
+    statistics: Option<Statistics>,
+    /// Missing from times set before boards were graded.
+    #[serde(rename = "g", default)]
+    difficulty: Option<Difficulty>,
+    /// The date of the daily challenge the time was set on, if it was.
+    #[serde(rename = "y", default)]
+    daily_date: Option<NaiveDate>,
+    /// Whether the assists made any moves during the game.
+    #[serde(rename = "a", default)]
+    assisted: bool,
+    #[serde(rename = "r", default)]
+    generation_mode: GenerationMode,
 }
 
 impl PartialOrd for LeaderboardEntry {
@@ -51,17 +95,30 @@ impl Ord for LeaderboardEntry {
             .time
             .cmp(&self.time)
             .then(
-                (self.width as u16 * self.height as u16 + self.mines)
-                    .cmp(&(other.width as u16 * other.height as u16 + other.mines)),
+                (self.width as u32 * self.height as u32 + self.mines)
+                    .cmp(&(other.width as u32 * other.height as u32 + other.mines)),
             )
             .then(other.completion_date.cmp(&self.completion_date))
             .then(self.name.cmp(&other.name))
+            .then(self.topology.cmp(&other.topology))
+            .then(self.first_click_policy.cmp(&other.first_click_policy))
+            .then(self.statistics.cmp(&other.statistics))
+            .then(self.difficulty.cmp(&other.difficulty))
+            .then(self.daily_date.cmp(&other.daily_date))
+            .then(self.assisted.cmp(&other.assisted))
+            .then(self.generation_mode.cmp(&other.generation_mode))
     }
 }
 
 #[derive(Clone, Debug)]
 pub enum Message {
     Back,
+    TabSelected(Tab),
+    ShowEasyBoardsToggled(bool),
+    ShowAssistedToggled(bool),
+    ShowNoGuessToggled(bool),
+    PreviousDay,
+    NextDay,
 }
 
 impl Leaderboard {
@@ -107,14 +164,30 @@ impl Leaderboard {
             entries,
             new_entry: None,
             current_tab: Tab::All,
+            show_easy_boards: false,
+            show_assisted: false,
+            show_no_guess: false,
+            daily_date: chrono::Local::now().date_naive(),
         }
     }
-    pub fn from_new_time(
-        config: ArcLock<Config>,
-        time: TimeDelta,
-        completion_date: DateTime<chrono::Utc>,
-        (width, height, mines): (u8, u8, u16),
-    ) -> Self {
+    /// Opens the leaderboard with the time of `game` added to it, unless undo
+    /// was used or the game had extra lives, in which case the time is not
+    /// ranked and the leaderboard is shown as is. Times set on a daily
+    /// challenge are shown on the daily tab, and times from assisted games are
+    /// ranked apart from the rest.
+    pub fn from_new_time(config: ArcLock<Config>, game: FinishedGame) -> Self {
+        let FinishedGame {
+            time,
+            completion_date,
+            board,
+            statistics,
+            daily_date,
+            assisted,
+            difficulty,
+        } = game;
+        if board.has_used_undo() || board.get_lives() > 1 {
+            return Self::from_menu(config);
+        }
         let mut entries = Self::load_entries().unwrap_or_else(|err| {
             eprintln!("Failed to load leaderboard: {err}");
             BTreeSet::new()
@@ -125,16 +198,32 @@ impl Leaderboard {
             name: whoami::realname(),
             time,
             completion_date,
-            width,
-            height,
-            mines,
+            width: board.get_width(),
+            height: board.get_height(),
+            mines: board.get_mine_count(),
+            topology: board.get_topology(),
+            first_click_policy: board.get_first_click_policy(),
+            statistics,
+            difficulty: Some(difficulty),
+            daily_date,
+            assisted,
+            generation_mode: board.get_generation_mode(),
         };
         entries.insert(new_entry.clone());
+        let show_no_guess = new_entry.generation_mode == GenerationMode::NoGuess;
         Self {
             config,
             entries,
             new_entry: Some(new_entry),
-            current_tab: Tab::All,
+            current_tab: if daily_date.is_some() {
+                Tab::Daily
+            } else {
+                Tab::All
+            },
+            show_easy_boards: false,
+            show_assisted: assisted,
+            show_no_guess,
+            daily_date: daily_date.unwrap_or_else(|| chrono::Local::now().date_naive()),
         }
     }
     fn entry_element(&self, entry: &LeaderboardEntry) -> Element<'_, SuperMessage> {
@@ -158,9 +247,38 @@ impl Leaderboard {
         let local_date = entry.completion_date.with_timezone(&chrono::Local);
         let date_string = local_date.format("%v, %I:%M%p").to_string();
         let date = config.text(date_string);
-        GuiWidget::row![name, time, date]
+        let statistics = entry.statistics.map(|statistics| {
+            let time = entry.time.to_std().unwrap_or_default();
+            config.text(format!(
+                "{:.2} 3BV/s, {:.2} IOE{}",
+                statistics.get_3bv_per_second(time),
+                statistics.get_efficiency(),
+                if statistics.is_no_flag() { ", NF" } else { "" }
+            ))
+        });
+        let row = GuiWidget::row![name, time, date, statistics]
             .spacing(20)
-            .align_y(iced::Alignment::Center)
+            .align_y(iced::Alignment::Center);
+        // Outline the time that was just set so that it can be found in the list
+        if self.new_entry.as_ref() == Some(entry) {
+            GuiWidget::container(row)
+                .padding(5)
+                .style(GuiWidget::container::bordered_box)
+                .into()
+        } else {
+            row.into()
+        }
+    }
+    fn tab_button(&self, tab: Tab, label: &'static str) -> Element<'_, SuperMessage> {
+        let menu_theme = &self.config.read().unwrap().menu_theme;
+        let style = if self.current_tab == tab {
+            crate::MenuButtonStyle::Primary
+        } else {
+            crate::MenuButtonStyle::Secondary
+        };
+        menu_theme
+            .button(menu_theme.text(label), style)
+            .on_press(SuperMessage::Leaderboard(Message::TabSelected(tab)))
             .into()
     }
 }
@@ -185,6 +303,30 @@ impl Screen for Leaderboard {
                     .map(SuperMessage::App),
                 )
             }
+            Message::TabSelected(tab) => {
+                self.current_tab = tab;
+                None
+            }
+            Message::ShowEasyBoardsToggled(show_easy_boards) => {
+                self.show_easy_boards = show_easy_boards;
+                None
+            }
+            Message::ShowAssistedToggled(show_assisted) => {
+                self.show_assisted = show_assisted;
+                None
+            }
+            Message::ShowNoGuessToggled(show_no_guess) => {
+                self.show_no_guess = show_no_guess;
+                None
+            }
+            Message::PreviousDay => {
+                self.daily_date = self.daily_date.pred_opt().unwrap_or(self.daily_date);
+                None
+            }
+            Message::NextDay => {
+                self.daily_date = self.daily_date.succ_opt().unwrap_or(self.daily_date);
+                None
+            }
         }
     }
     fn view(&self) -> Element<'_, SuperMessage> {
@@ -193,8 +335,42 @@ impl Screen for Leaderboard {
 
         let entries = self.entries.iter().rev();
         let entry_elements = entries
+            // Times set under different first-click rules are not comparable, so only the
+            // ones following the current rules are shown. The daily challenge makes the
+            // first click for everyone, so its times are always comparable.
+            .filter(|entry| {
+                entry.daily_date.is_some() || entry.first_click_policy == config.first_click_policy
+            })
+            // Times set on unusually easy boards are hidden unless asked for, as they would
+            // crowd out the times that took real skill. Everyone plays the same daily board,
+            // so its times are shown however easy it is.
+            .filter(|entry| {
+                self.show_easy_boards
+                    || entry.daily_date.is_some()
+                    || entry.difficulty != Some(Difficulty::Easy)
+            })
+            // Assisted games are ranked on their own, as the assists make moves for the player
+            .filter(|entry| entry.assisted == self.show_assisted)
+            // No-guess boards never need luck, so they are ranked apart from random ones
+            .filter(|entry| {
+                entry.daily_date.is_some()
+                    || (entry.generation_mode == GenerationMode::NoGuess) == self.show_no_guess
+            })
             .filter_map(|entry| match &self.current_tab {
                 Tab::All => Some(self.entry_element(entry)),
+                Tab::Daily => {
+                    (entry.daily_date == Some(self.daily_date)).then(|| self.entry_element(entry))
+                }
+                // Everyone plays the same daily board, so its times are only ranked against
+                // each other
+                _ if entry.daily_date.is_some() => None,
+                Tab::Wrapping => (entry.topology == Topology::Wrapping)
+                    .then(|| self.entry_element(entry)),
+                Tab::Hexagonal => (entry.topology == Topology::Hexagonal)
+                    .then(|| self.entry_element(entry)),
+                // Other topologies are ranked apart from the standard difficulties, since
+                // how the cells connect changes how hard a board is
+                _ if entry.topology != Topology::Bounded => None,
                 Tab::Beginner => {
                     if entry.width == 9 && entry.height == 9 && entry.mines == 10 {
                         Some(self.entry_element(entry))
@@ -232,7 +408,67 @@ impl Screen for Leaderboard {
             .button(menu_theme.text("Back"), crate::MenuButtonStyle::Secondary)
             .on_press(SuperMessage::Leaderboard(Message::Back));
 
-        let content = GuiWidget::column![entries_content, back_button]
+        let tabs = GuiWidget::row![
+            self.tab_button(Tab::All, "All"),
+            self.tab_button(Tab::Beginner, "Beginner"),
+            self.tab_button(Tab::Intermediate, "Intermediate"),
+            self.tab_button(Tab::Expert, "Expert"),
+            self.tab_button(Tab::Wrapping, "Wrap-around"),
+            self.tab_button(Tab::Hexagonal, "Hexagonal"),
+            self.tab_button(Tab::Daily, "Daily")
+        ]
+        .spacing(10);
+
+        let rules_text = menu_theme.text(format!(
+            "First click: {}",
+            config.first_click_policy
+        ));
+
+        let easy_boards_checkbox = GuiWidget::checkbox(self.show_easy_boards)
+            .label("Show times from easy boards")
+            .on_toggle(|show_easy_boards| {
+                SuperMessage::Leaderboard(Message::ShowEasyBoardsToggled(show_easy_boards))
+            })
+            .font(menu_theme.default_font())
+            .text_size(menu_theme.default_text_size());
+        let daily_picker = (self.current_tab == Tab::Daily).then(|| {
+            GuiWidget::row![
+                menu_theme
+                    .button(menu_theme.text("<"), crate::MenuButtonStyle::Secondary)
+                    .on_press(SuperMessage::Leaderboard(Message::PreviousDay)),
+                menu_theme.text(self.daily_date.format("%-d %B %Y").to_string()),
+                menu_theme
+                    .button(menu_theme.text(">"), crate::MenuButtonStyle::Secondary)
+                    .on_press(SuperMessage::Leaderboard(Message::NextDay))
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+        });
+        let assisted_checkbox = GuiWidget::checkbox(self.show_assisted)
+            .label("Assisted games")
+            .on_toggle(|show_assisted| {
+                SuperMessage::Leaderboard(Message::ShowAssistedToggled(show_assisted))
+            })
+            .font(menu_theme.default_font())
+            .text_size(menu_theme.default_text_size());
+        let no_guess_checkbox = GuiWidget::checkbox(self.show_no_guess)
+            .label("No-guess boards")
+            .on_toggle(|show_no_guess| {
+                SuperMessage::Leaderboard(Message::ShowNoGuessToggled(show_no_guess))
+            })
+            .font(menu_theme.default_font())
+            .text_size(menu_theme.default_text_size());
+        let filters = GuiWidget::row![
+            daily_picker,
+            rules_text,
+            easy_boards_checkbox,
+            assisted_checkbox,
+            no_guess_checkbox
+        ]
+        .spacing(20)
+        .align_y(iced::Alignment::Center);
+
+        let content = GuiWidget::column![tabs, filters, entries_content, back_button]
             .spacing(20)
             .align_x(iced::Alignment::Center);
 
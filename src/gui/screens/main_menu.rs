@@ -3,14 +3,17 @@
 use iced::{Element, Task, widget as GuiWidget};
 
 use super::{
-    About, AppMessage, GameSelection, Leaderboard, Message as SuperMessage, SettingsScreen,
+    About, AppMessage, Game, GameSelection, Leaderboard, Message as SuperMessage, Replay,
+    SettingsScreen,
 };
 use crate::{ArcLock, Config, Screen};
 
 #[derive(Debug, Clone)]
 pub enum Message {
+    Continue,
     ToGameSelection,
     ToLeaderboard,
+    ToReplays,
     ToSettings,
     ToAbout,
     Quit,
@@ -19,11 +22,15 @@ pub enum Message {
 #[derive(Debug)]
 pub struct MainMenu {
     config: ArcLock<Config>,
+    can_continue: bool,
 }
 
 impl MainMenu {
     pub fn build(config: ArcLock<Config>) -> Self {
-        Self { config }
+        Self {
+            config,
+            can_continue: Game::has_saved_game(),
+        }
     }
 }
 
@@ -34,6 +41,20 @@ impl Screen for MainMenu {
         };
         let config = self.config.clone();
         match message {
+            Message::Continue => match Game::restore(config) {
+                Ok(game) => Some(
+                    Task::perform(async { game }, move |item| {
+                        Arc::new(Box::new(item) as Box<dyn Screen>)
+                    })
+                    .map(AppMessage::ChangeScreen)
+                    .map(SuperMessage::App),
+                ),
+                Err(err) => {
+                    eprintln!("Failed to continue game: {err}");
+                    self.can_continue = Game::has_saved_game();
+                    None
+                }
+            },
             Message::ToGameSelection => Some(
                 Task::perform(async { GameSelection::build(config) }, move |item| {
                     Arc::new(Box::new(item) as Box<dyn Screen>)
@@ -48,6 +69,13 @@ impl Screen for MainMenu {
                 .map(AppMessage::ChangeScreen)
                 .map(SuperMessage::App),
             ),
+            Message::ToReplays => Some(
+                Task::perform(async { Replay::from_menu(config) }, move |item| {
+                    Arc::new(Box::new(item) as Box<dyn Screen>)
+                })
+                .map(AppMessage::ChangeScreen)
+                .map(SuperMessage::App),
+            ),
             Message::ToSettings => Some(
                 Task::perform(async { SettingsScreen::build(config) }, move |item| {
                     Arc::new(Box::new(item) as Box<dyn Screen>)
@@ -75,12 +103,20 @@ impl Screen for MainMenu {
         let play_button = menu_theme
             .button(menu_theme.text("Play"), crate::MenuButtonStyle::Primary)
             .on_press(SuperMessage::MainMenu(Message::ToGameSelection));
+        let continue_button = self.can_continue.then(|| {
+            menu_theme
+                .button(menu_theme.text("Continue"), crate::MenuButtonStyle::Primary)
+                .on_press(SuperMessage::MainMenu(Message::Continue))
+        });
         let leaderboard_button = menu_theme
             .button(
                 menu_theme.text("Leaderboard"),
                 crate::MenuButtonStyle::Secondary,
             )
             .on_press(SuperMessage::MainMenu(Message::ToLeaderboard));
+        let replays_button = menu_theme
+            .button(menu_theme.text("Replays"), crate::MenuButtonStyle::Secondary)
+            .on_press(SuperMessage::MainMenu(Message::ToReplays));
         let settings_button = menu_theme
             .button(
                 menu_theme.text("Settings"),
@@ -95,8 +131,10 @@ impl Screen for MainMenu {
             .on_press(SuperMessage::MainMenu(Message::Quit));
 
         let buttons = GuiWidget::column![
+            continue_button,
             play_button,
             leaderboard_button,
+            replays_button,
             settings_button,
             about_button,
             quit_button
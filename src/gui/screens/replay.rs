@@ -0,0 +1,447 @@
+﻿use std::{
+    num::{NonZeroU16, NonZeroU32},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use iced::{Element, Subscription, Task, widget as GuiWidget};
+
+use super::{AppMessage, Game, MainMenu, Message as SuperMessage};
+use crate::{Application, ArcLock, Board, Config, Screen, Topology};
+
+/// The version of the replay format, which is bumped whenever the format
+/// changes in a way that older replays can no longer be read.
+const REPLAY_VERSION: u32 = 2;
+
+/// The playback speeds that can be chosen from, as multiples of real time.
+const SPEEDS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+
+/// An action made by the player whilst playing a game.
+#[derive(Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum RecordedAction {
+    OpenCell(u16, u16),
+    ToggleFlag(u16, u16),
+    ChordCell(u16, u16),
+    CycleMark(u16, u16),
+    Undo,
+    Redo,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct RecordedEvent {
+    #[serde(rename = "t")]
+    time: Duration,
+    #[serde(rename = "a")]
+    action: RecordedAction,
+}
+
+/// Everything needed to play a game back: the layout of the mines, along with
+/// every action the player made and when they made it.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Recording {
+    #[serde(rename = "v")]
+    version: u32,
+    #[serde(with = "chrono::serde::ts_milliseconds", rename = "d")]
+    start_date: DateTime<Utc>,
+    #[serde(rename = "w")]
+    width: u16,
+    #[serde(rename = "h")]
+    height: u16,
+    #[serde(rename = "m")]
+    mine_count: u32,
+    #[serde(rename = "o", default)]
+    topology: Topology,
+    /// How many lives the game had beyond the first.
+    #[serde(rename = "i", default)]
+    extra_lives: u32,
+    #[serde(rename = "l")]
+    mined_coordinates: Vec<(u16, u16)>,
+    #[serde(rename = "e")]
+    events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    pub fn create(board: &Board) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            start_date: Utc::now(),
+            width: board.get_width(),
+            height: board.get_height(),
+            mine_count: board.get_mine_count(),
+            topology: board.get_topology(),
+            extra_lives: board.get_lives() - 1,
+            mined_coordinates: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+    /// Records `action` as having been made `time` after the game started.
+    pub fn record(&mut self, time: Duration, action: RecordedAction) {
+        self.events.push(RecordedEvent { time, action });
+    }
+    /// Takes the layout of the mines from the finished `board` and writes the
+    /// replay into the data directory. Saving the same game again overwrites
+    /// the earlier replay of it.
+    pub fn save(&mut self, board: &Board) -> Result<(), Box<dyn std::error::Error>> {
+        self.mined_coordinates = board
+            .iter_cells()
+            .filter(|(_, cell)| cell.is_mine())
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        let replay_dir = Self::replay_dir();
+        if !replay_dir.exists() {
+            std::fs::create_dir_all(&replay_dir)?;
+        }
+        let file_name = self.start_date.format("%Y%m%d-%H%M%S%.3f.replay").to_string();
+        let file = std::fs::File::create(replay_dir.join(file_name))?;
+        ciborium::into_writer(self, file)?;
+        Ok(())
+    }
+    fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(path)?;
+        let recording: Self = ciborium::from_reader(file)?;
+        if recording.version != REPLAY_VERSION {
+            return Err(format!("unsupported replay version {}", recording.version).into());
+        }
+        Ok(recording)
+    }
+    fn load_all() -> Vec<Self> {
+        let Ok(directory) = std::fs::read_dir(Self::replay_dir()) else {
+            return Vec::new();
+        };
+        let mut recordings = directory
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "replay"))
+            .filter_map(|path| {
+                Self::load(&path)
+                    .inspect_err(|err| {
+                        eprintln!("Failed to load replay {}: {err}", path.display());
+                    })
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        // Newest first
+        recordings.sort_by_key(|recording| std::cmp::Reverse(recording.start_date));
+        recordings
+    }
+    fn replay_dir() -> PathBuf {
+        Application::app_dirs().data_dir().join("replays")
+    }
+    /// Creates the board as it was before the first action, with its mines
+    /// already placed. Returns `None` if the recording does not describe a
+    /// valid board.
+    fn create_board(&self) -> Option<Board> {
+        let board = Board::create_with_mines(
+            NonZeroU16::new(self.width)?,
+            NonZeroU16::new(self.height)?,
+            &self.mined_coordinates,
+        )
+        .ok()?
+        .with_topology(self.topology)
+        .with_lives(NonZeroU32::MIN.saturating_add(self.extra_lives));
+        (board.get_mine_count() == self.mine_count).then_some(board)
+    }
+    fn get_duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::ZERO, |event| event.time)
+    }
+}
+
+fn apply_action(board: &mut Board, action: RecordedAction) {
+    match action {
+        RecordedAction::OpenCell(x, y) => {
+            board.open_cell(x, y);
+        }
+        RecordedAction::ToggleFlag(x, y) => {
+            board.toggle_flag(x, y);
+        }
+        RecordedAction::ChordCell(x, y) => {
+            board.chord_cell(x, y);
+        }
+        RecordedAction::CycleMark(x, y) => {
+            board.cycle_mark(x, y);
+        }
+        RecordedAction::Undo => {
+            board.undo();
+        }
+        RecordedAction::Redo => {
+            board.redo();
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ReplaySelected(usize),
+    Tick(Instant),
+    TogglePlaying,
+    Seek(f64),
+    SpeedSelected(f32),
+    CloseReplay,
+    Back,
+}
+
+#[derive(Debug)]
+struct Playback {
+    recording: Recording,
+    initial_board: Board,
+    board: Board,
+    next_event: usize,
+    position: Duration,
+    playing: bool,
+    speed: f32,
+    last_tick: Option<Instant>,
+}
+
+impl Playback {
+    fn create(recording: Recording) -> Option<Self> {
+        let initial_board = recording.create_board()?;
+        Some(Self {
+            recording,
+            board: initial_board.clone(),
+            initial_board,
+            next_event: 0,
+            position: Duration::ZERO,
+            playing: true,
+            speed: 1.0,
+            last_tick: None,
+        })
+    }
+    /// Moves playback to `position`, applying every action made up to then.
+    fn seek(&mut self, position: Duration) {
+        let position = position.min(self.recording.get_duration());
+        if position < self.position {
+            // Actions cannot be taken back one by one, so going backwards replays the
+            // game from the start.
+            self.board = self.initial_board.clone();
+            self.next_event = 0;
+        }
+        while let Some(event) = self.recording.events.get(self.next_event) {
+            if event.time > position {
+                break;
+            }
+            apply_action(&mut self.board, event.action);
+            self.next_event += 1;
+        }
+        self.position = position;
+    }
+    fn is_finished(&self) -> bool {
+        self.next_event == self.recording.events.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct Replay {
+    config: ArcLock<Config>,
+    recordings: Vec<Recording>,
+    playback: Option<Playback>,
+}
+
+impl Replay {
+    pub fn from_menu(config: ArcLock<Config>) -> Self {
+        Self {
+            config,
+            recordings: Recording::load_all(),
+            playback: None,
+        }
+    }
+    pub fn build(config: ArcLock<Config>, recording: Recording) -> Self {
+        let mut replay = Self::from_menu(config);
+        replay.playback = Playback::create(recording);
+        replay
+    }
+    fn replay_list(&self) -> Element<'_, SuperMessage> {
+        let menu_theme = &self.config.read().unwrap().menu_theme;
+        let recording_buttons = self
+            .recordings
+            .iter()
+            .enumerate()
+            .map(|(index, recording)| {
+                let local_date = recording.start_date.with_timezone(&chrono::Local);
+                let label = format!(
+                    "{}  {}x{}, {} mines",
+                    local_date.format("%v, %I:%M%p"),
+                    recording.width,
+                    recording.height,
+                    recording.mine_count
+                );
+                menu_theme
+                    .button(menu_theme.text(label), crate::MenuButtonStyle::Secondary)
+                    .on_press(SuperMessage::Replay(Message::ReplaySelected(index)))
+                    .into()
+            })
+            .collect::<Vec<_>>();
+        let recordings_content = if recording_buttons.is_empty() {
+            GuiWidget::container(menu_theme.text("No replays have been saved yet."))
+        } else {
+            GuiWidget::container(GuiWidget::scrollable(
+                GuiWidget::column(recording_buttons)
+                    .spacing(10)
+                    .width(iced::Fill),
+            ))
+        }
+        .padding(10)
+        .height(iced::Fill)
+        .width(iced::Fill)
+        .style(GuiWidget::container::bordered_box);
+
+        let back_button = menu_theme
+            .button(menu_theme.text("Back"), crate::MenuButtonStyle::Secondary)
+            .on_press(SuperMessage::Replay(Message::Back));
+
+        let content = GuiWidget::column![recordings_content, back_button]
+            .spacing(20)
+            .align_x(iced::Alignment::Center);
+        GuiWidget::center(content).padding(10).into()
+    }
+    fn playback_view<'a>(&'a self, playback: &'a Playback) -> Element<'a, SuperMessage> {
+        let config = self.config.read().unwrap();
+        let menu_theme = &config.menu_theme;
+
+        let board = &playback.board;
+        let board_content = Game::board_layout(
+            board,
+            |x, y| {
+                Game::cell_content(
+                    &config.game_theme,
+                    board.get_state(),
+                    board.get_cell(x, y).unwrap(),
+                )
+            },
+            |_, gap| gap,
+        );
+        let board_content = GuiWidget::container(board_content)
+            .style(GuiWidget::container::bordered_box)
+            .padding(10);
+
+        let duration = playback.recording.get_duration().as_secs_f64();
+        let position = playback.position.as_secs_f64();
+        let time_text = menu_theme.text(format!("{position:.1}s / {duration:.1}s"));
+        let seek_slider = GuiWidget::slider(0.0..=duration, position, |position| {
+            SuperMessage::Replay(Message::Seek(position))
+        })
+        .step(0.1)
+        .width((board.get_width() as usize * 16).max(200) as f32);
+
+        let play_label = if playback.playing { "Pause" } else { "Play" };
+        let play_button = menu_theme
+            .button(menu_theme.text(play_label), crate::MenuButtonStyle::Primary)
+            .on_press(SuperMessage::Replay(Message::TogglePlaying));
+        let mut controls = GuiWidget::row![play_button]
+            .spacing(10)
+            .align_y(iced::Center);
+        for speed in SPEEDS {
+            let style = if speed == playback.speed {
+                crate::MenuButtonStyle::Primary
+            } else {
+                crate::MenuButtonStyle::Secondary
+            };
+            controls = controls.push(
+                menu_theme
+                    .button(menu_theme.text(format!("{speed}x")), style)
+                    .on_press(SuperMessage::Replay(Message::SpeedSelected(speed))),
+            );
+        }
+
+        let close_button = menu_theme
+            .button(
+                menu_theme.text("Close replay"),
+                crate::MenuButtonStyle::Secondary,
+            )
+            .on_press(SuperMessage::Replay(Message::CloseReplay));
+
+        let content = GuiWidget::column![
+            board_content,
+            time_text,
+            seek_slider,
+            controls,
+            close_button
+        ]
+        .spacing(10)
+        .align_x(iced::Center);
+        GuiWidget::center(content).into()
+    }
+}
+
+impl Screen for Replay {
+    fn update(&mut self, message: SuperMessage) -> Option<Task<SuperMessage>> {
+        let SuperMessage::Replay(message) = message else {
+            return None;
+        };
+        let config = self.config.clone();
+        match message {
+            Message::ReplaySelected(index) => {
+                self.playback = self
+                    .recordings
+                    .get(index)
+                    .cloned()
+                    .and_then(Playback::create);
+                if self.playback.is_none() {
+                    eprintln!("Failed to play replay: the recorded board is invalid");
+                }
+                None
+            }
+            Message::Tick(now) => {
+                let playback = self.playback.as_mut()?;
+                if let Some(last_tick) = playback.last_tick {
+                    let elapsed = now.duration_since(last_tick).mul_f32(playback.speed);
+                    playback.seek(playback.position + elapsed);
+                }
+                playback.last_tick = Some(now);
+                if playback.is_finished() {
+                    playback.playing = false;
+                }
+                None
+            }
+            Message::TogglePlaying => {
+                let playback = self.playback.as_mut()?;
+                if !playback.playing && playback.is_finished() {
+                    playback.seek(Duration::ZERO);
+                }
+                playback.playing = !playback.playing;
+                playback.last_tick = None;
+                None
+            }
+            Message::Seek(position) => {
+                let playback = self.playback.as_mut()?;
+                playback.seek(Duration::from_secs_f64(position));
+                None
+            }
+            Message::SpeedSelected(speed) => {
+                let playback = self.playback.as_mut()?;
+                playback.speed = speed;
+                None
+            }
+            Message::CloseReplay => {
+                self.playback = None;
+                None
+            }
+            Message::Back => Some(
+                Task::perform(async { MainMenu::build(config) }, move |item| {
+                    Arc::new(Box::new(item) as Box<dyn Screen>)
+                })
+                .map(AppMessage::ChangeScreen)
+                .map(SuperMessage::App),
+            ),
+        }
+    }
+    fn view(&self) -> Element<'_, SuperMessage> {
+        match &self.playback {
+            Some(playback) => self.playback_view(playback),
+            None => self.replay_list(),
+        }
+    }
+    fn subscription(&self) -> Option<Subscription<SuperMessage>> {
+        self.playback
+            .as_ref()
+            .filter(|playback| playback.playing)
+            .map(|_| {
+                iced::time::every(Duration::from_millis(50))
+                    .map(Message::Tick)
+                    .map(SuperMessage::Replay)
+            })
+    }
+}
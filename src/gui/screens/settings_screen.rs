@@ -3,7 +3,7 @@
 use iced::{Element, Task, widget as GuiWidget};
 
 use super::{AppMessage, Leaderboard, MainMenu, Message as SuperMessage};
-use crate::{ArcLock, Config, GameTheme, MenuTheme, Screen};
+use crate::{ArcLock, ChordStyle, Config, FirstClickPolicy, GameTheme, MenuTheme, Screen};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -11,6 +11,11 @@ pub enum Message {
     MenuThemeChanged(MenuTheme),
     GameThemeChanged(GameTheme),
     ScaleFactorChanged(f32),
+    QuestionMarksToggled(bool),
+    FirstClickPolicyChanged(FirstClickPolicy),
+    AutoFlagToggled(bool),
+    AutoOpenToggled(bool),
+    ChordStyleChanged(ChordStyle),
     ApplyChanges,
     ResetChanges,
     LeaderboardReset(LeaderboardReset),
@@ -29,6 +34,11 @@ pub struct SettingsScreen {
     menu_theme: Option<MenuTheme>,
     game_theme: Option<GameTheme>,
     scale_factor: Option<f32>,
+    question_marks: Option<bool>,
+    first_click_policy: Option<FirstClickPolicy>,
+    auto_flag: Option<bool>,
+    auto_open: Option<bool>,
+    chord_style: Option<ChordStyle>,
     showing_confirmation: bool,
 }
 
@@ -39,6 +49,11 @@ impl SettingsScreen {
             menu_theme: None,
             game_theme: None,
             scale_factor: None,
+            question_marks: None,
+            first_click_policy: None,
+            auto_flag: None,
+            auto_open: None,
+            chord_style: None,
             showing_confirmation: false,
         }
     }
@@ -70,6 +85,26 @@ impl Screen for SettingsScreen {
                 self.scale_factor = Some(factor);
                 None
             }
+            Message::QuestionMarksToggled(question_marks) => {
+                self.question_marks = Some(question_marks);
+                None
+            }
+            Message::FirstClickPolicyChanged(first_click_policy) => {
+                self.first_click_policy = Some(first_click_policy);
+                None
+            }
+            Message::AutoFlagToggled(auto_flag) => {
+                self.auto_flag = Some(auto_flag);
+                None
+            }
+            Message::AutoOpenToggled(auto_open) => {
+                self.auto_open = Some(auto_open);
+                None
+            }
+            Message::ChordStyleChanged(chord_style) => {
+                self.chord_style = Some(chord_style);
+                None
+            }
             Message::ApplyChanges => {
                 let mut config_write = self.config.write().unwrap();
                 if let Some(ref menu_theme) = self.menu_theme {
@@ -81,6 +116,21 @@ impl Screen for SettingsScreen {
                 if let Some(scale_factor) = self.scale_factor {
                     config_write.scale_factor = scale_factor;
                 }
+                if let Some(question_marks) = self.question_marks {
+                    config_write.question_marks = question_marks;
+                }
+                if let Some(first_click_policy) = self.first_click_policy {
+                    config_write.first_click_policy = first_click_policy;
+                }
+                if let Some(auto_flag) = self.auto_flag {
+                    config_write.auto_flag = auto_flag;
+                }
+                if let Some(auto_open) = self.auto_open {
+                    config_write.auto_open = auto_open;
+                }
+                if let Some(chord_style) = self.chord_style {
+                    config_write.chord_style = chord_style;
+                }
                 Some(Task::done(SuperMessage::SettingsScreen(
                     Message::ResetChanges,
                 )))
@@ -89,6 +139,11 @@ impl Screen for SettingsScreen {
                 self.menu_theme = None;
                 self.game_theme = None;
                 self.scale_factor = None;
+                self.question_marks = None;
+                self.first_click_policy = None;
+                self.auto_flag = None;
+                self.auto_open = None;
+                self.chord_style = None;
                 None
             }
             Message::LeaderboardReset(action) => match action {
@@ -185,6 +240,61 @@ impl Screen for SettingsScreen {
                 .align_y(iced::Center)
                 .spacing(10);
 
+        let question_marks_checkbox = GuiWidget::checkbox(
+            self.question_marks
+                .unwrap_or_else(|| self.config.read().unwrap().question_marks),
+        )
+        .label("Right-click places question marks")
+        .on_toggle(|question_marks| {
+            SuperMessage::SettingsScreen(Message::QuestionMarksToggled(question_marks))
+        })
+        .font(default_font)
+        .text_size(text_size);
+
+        let first_click_text = menu_theme.text("First Click:");
+        let first_click_picker = GuiWidget::pick_list(
+            FirstClickPolicy::ALL,
+            self.first_click_policy,
+            |first_click_policy| {
+                SuperMessage::SettingsScreen(Message::FirstClickPolicyChanged(first_click_policy))
+            },
+        )
+        .font(default_font)
+        .text_size(text_size)
+        .placeholder(self.config.read().unwrap().first_click_policy.to_string());
+        let first_click = GuiWidget::row![first_click_text, first_click_picker]
+            .align_y(iced::Center)
+            .spacing(10);
+
+        let chord_style_text = menu_theme.text("Chord With:");
+        let chord_style_picker =
+            GuiWidget::pick_list(ChordStyle::ALL, self.chord_style, |chord_style| {
+                SuperMessage::SettingsScreen(Message::ChordStyleChanged(chord_style))
+            })
+            .font(default_font)
+            .text_size(text_size)
+            .placeholder(self.config.read().unwrap().chord_style.to_string());
+        let chord_style = GuiWidget::row![chord_style_text, chord_style_picker]
+            .align_y(iced::Center)
+            .spacing(10);
+
+        let auto_flag_checkbox = GuiWidget::checkbox(
+            self.auto_flag
+                .unwrap_or_else(|| self.config.read().unwrap().auto_flag),
+        )
+        .label("Flag obvious mines automatically")
+        .on_toggle(|auto_flag| SuperMessage::SettingsScreen(Message::AutoFlagToggled(auto_flag)))
+        .font(default_font)
+        .text_size(text_size);
+        let auto_open_checkbox = GuiWidget::checkbox(
+            self.auto_open
+                .unwrap_or_else(|| self.config.read().unwrap().auto_open),
+        )
+        .label("Open around fully flagged numbers automatically")
+        .on_toggle(|auto_open| SuperMessage::SettingsScreen(Message::AutoOpenToggled(auto_open)))
+        .font(default_font)
+        .text_size(text_size);
+
         let reset_leaderboard_button = menu_theme
             .button(
                 menu_theme.text("Reset Leaderboard"),
@@ -198,6 +308,11 @@ impl Screen for SettingsScreen {
             menu_theme_row,
             game_theme,
             scale_factor,
+            question_marks_checkbox,
+            first_click,
+            chord_style,
+            auto_flag_checkbox,
+            auto_open_checkbox,
             reset_leaderboard_button
         ]
         .spacing(10);
@@ -6,6 +6,6 @@ pub use core::{board::*, cell::*};
 
 pub use gui::{
     Application, ArcLock, Screen, assets,
-    config::{Config, GameTheme, MenuButtonStyle, MenuTheme},
+    config::{ChordStyle, Config, GameTheme, MenuButtonStyle, MenuTheme},
     scale_factor, subscription, theme, update, view,
 };